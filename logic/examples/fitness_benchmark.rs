@@ -0,0 +1,147 @@
+//! Times sequential against parallel fitness evaluation, whole solver runs,
+//! and runs with and without repair, on a synthetic 500-course term.
+//!
+//! cargo run --release -p logic --example fitness_benchmark [threads]
+
+use std::time::Instant;
+
+use chrono::NaiveTime;
+use logic::ga::generate_random_schedule;
+use logic::{genetic_algorithm, Candidates, Course, Days, FitnessModel, GaConfig, Instructor, LoadLimits, Problem, Room, Student};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+const COURSES: usize = 500;
+const INSTRUCTORS: usize = 120;
+const ROOMS: usize = 90;
+const STUDENTS: usize = 5_000;
+const POPULATION: usize = 256;
+const GENERATIONS: usize = 20;
+
+fn synthetic_problem(rng: &mut StdRng) -> Problem {
+    let names: Vec<String> = (0..COURSES).map(|i| format!("Course {}", i)).collect();
+
+    let courses = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let course = if i % 2 == 0 {
+                Course::new(name, 60, 180)
+            } else {
+                Course::new(name, 90, 180)
+            };
+            course
+                .with_enrollment(rng.random_range(10..60))
+                .for_groups(&[&format!("Cohort {}", i % 40)])
+        })
+        .collect();
+
+    let instructors = (0..INSTRUCTORS)
+        .map(|i| {
+            // Every course gets three qualified instructors.
+            let qualified: Vec<&str> = names
+                .iter()
+                .enumerate()
+                .filter(|(c, _)| (c % INSTRUCTORS) / 3 == i / 3)
+                .map(|(_, name)| name.as_str())
+                .collect();
+            Instructor::new(&format!("Instructor {}", i), &qualified).with_limits(LoadLimits {
+                max_daily_minutes: Some(360),
+                max_weekly_minutes: Some(1200),
+                max_consecutive: Some(3),
+            })
+        })
+        .collect();
+
+    let rooms = (0..ROOMS)
+        .map(|i| Room::new(&format!("Room {}", i), 60))
+        .collect();
+
+    let times: Vec<NaiveTime> = (0..16)
+        .map(|i| NaiveTime::from_hms_opt(8, 0, 0).unwrap() + chrono::Duration::minutes(30 * i))
+        .collect();
+
+    let students = (0..STUDENTS)
+        .map(|i| {
+            let enrolled: Vec<&str> = names.choose_multiple(rng, 5).map(String::as_str).collect();
+            Student::new(&format!("Student {}", i), &enrolled)
+        })
+        .collect();
+
+    Problem::new(courses, instructors, rooms, &[Days::MWF, Days::TTH], &times).with_students(students)
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(500);
+    let problem = synthetic_problem(&mut rng);
+    problem.validate().expect("synthetic problem is valid");
+    let model = FitnessModel::for_problem(&problem);
+    let candidates = Candidates::new(&problem);
+
+    let population: Vec<_> = (0..POPULATION)
+        .map(|_| generate_random_schedule(&candidates, &mut rng))
+        .collect();
+
+    let threads = match std::env::args().nth(1) {
+        Some(threads) => threads.parse().expect("thread count"),
+        None => GaConfig::default().worker_threads(),
+    };
+
+    let started = Instant::now();
+    let sequential = model.evaluate_all(&problem, &population, 1);
+    let sequential_time = started.elapsed();
+
+    let started = Instant::now();
+    let parallel = model.evaluate_all(&problem, &population, threads);
+    let parallel_time = started.elapsed();
+
+    assert_eq!(sequential, parallel);
+    println!("{} schedules of {} classes", POPULATION, COURSES);
+    println!("sequential: {:.2?}", sequential_time);
+    println!("parallel:   {:.2?} on {} threads", parallel_time, threads);
+    println!("speedup:    {:.2}x", sequential_time.as_secs_f64() / parallel_time.as_secs_f64());
+
+    let config = |threads, islands| GaConfig {
+        generations: GENERATIONS,
+        islands,
+        threads,
+        seed: Some(500),
+        ..GaConfig::default()
+    };
+    for islands in [1, 4] {
+        let started = Instant::now();
+        let sequential = genetic_algorithm(&problem, &model, &config(1, islands)).expect("valid run");
+        let sequential_time = started.elapsed();
+
+        let started = Instant::now();
+        let parallel = genetic_algorithm(&problem, &model, &config(threads, islands)).expect("valid run");
+        let parallel_time = started.elapsed();
+
+        assert_eq!(sequential.schedule, parallel.schedule);
+        println!("{} generations on {} island(s)", GENERATIONS, islands);
+        println!("sequential: {:.2?}", sequential_time);
+        println!("parallel:   {:.2?} on {} threads", parallel_time, threads);
+        println!("speedup:    {:.2}x", sequential_time.as_secs_f64() / parallel_time.as_secs_f64());
+    }
+
+    let started = Instant::now();
+    let plain = genetic_algorithm(&problem, &model, &config(threads, 1)).expect("valid run");
+    let plain_time = started.elapsed();
+
+    let started = Instant::now();
+    let repaired = genetic_algorithm(
+        &problem,
+        &model,
+        &GaConfig {
+            repair: true,
+            ..config(threads, 1)
+        },
+    )
+    .expect("valid run");
+    let repaired_time = started.elapsed();
+
+    println!("{} generations with repair", GENERATIONS);
+    println!("without: {:.2?}, hard penalty {}", plain_time, plain.fitness.hard);
+    println!("with:    {:.2?}, hard penalty {}", repaired_time, repaired.fitness.hard);
+    println!("cost:    {:.2}x", repaired_time.as_secs_f64() / plain_time.as_secs_f64());
+}
@@ -0,0 +1,147 @@
+use chrono::Duration;
+use rand::prelude::*;
+
+use crate::chromosome::Gene;
+use crate::problem::{Instructor, Problem};
+
+/// Where each course of a problem may be placed: its qualified instructors,
+/// suitable rooms and the slots a meeting of it fits in. Working these out
+/// once per run spares every mutation and repair from rescanning the problem.
+#[derive(Debug, Clone)]
+pub struct Candidates {
+    instructors: Vec<Vec<usize>>,
+    rooms: Vec<Vec<usize>>,
+    slots: Vec<Vec<usize>>,
+    /// For each course and each of its instructors, in the same order, the
+    /// slots that instructor prefers, or failing that is available in, or
+    /// failing that all of the course's slots.
+    favoured: Vec<Vec<Vec<usize>>>,
+}
+
+impl Candidates {
+    /// The candidates of every course of `problem`, which should have passed
+    /// [`Problem::validate`] so that none of the lists is empty.
+    pub fn new(problem: &Problem) -> Self {
+        let instructors: Vec<Vec<usize>> = problem
+            .courses
+            .iter()
+            .map(|course| problem.qualified_instructor_indices(course))
+            .collect();
+        let slots: Vec<Vec<usize>> = problem.courses.iter().map(|course| problem.slots_for(course)).collect();
+
+        let favoured = problem
+            .courses
+            .iter()
+            .zip(&instructors)
+            .zip(&slots)
+            .map(|((course, instructors), slots)| {
+                instructors
+                    .iter()
+                    .map(|&instructor| {
+                        let instructor = &problem.instructors[instructor];
+                        let fits = |slot: usize, allowed: fn(&Instructor, _, _, _) -> bool| {
+                            let (days, start) = problem.slot(slot);
+                            allowed(instructor, days, start, start + Duration::minutes(course.duration))
+                        };
+                        let available: Vec<usize> = slots
+                            .iter()
+                            .copied()
+                            .filter(|&slot| fits(slot, Instructor::is_available))
+                            .collect();
+                        let preferred: Vec<usize> = available
+                            .iter()
+                            .copied()
+                            .filter(|&slot| fits(slot, Instructor::prefers))
+                            .collect();
+                        [preferred, available]
+                            .into_iter()
+                            .find(|candidates| !candidates.is_empty())
+                            .unwrap_or_else(|| slots.clone())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            instructors,
+            rooms: problem
+                .courses
+                .iter()
+                .map(|course| problem.suitable_room_indices(course))
+                .collect(),
+            slots,
+            favoured,
+        }
+    }
+
+    /// Number of courses, and so of classes in a schedule.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Instructors qualified to teach course `course`, by index.
+    pub fn instructors(&self, course: usize) -> &[usize] {
+        &self.instructors[course]
+    }
+
+    /// Rooms large and equipped enough for course `course`, by index.
+    pub fn rooms(&self, course: usize) -> &[usize] {
+        &self.rooms[course]
+    }
+
+    /// Slots a meeting of course `course` may be given, see [`Problem::slots_for`].
+    pub fn slots(&self, course: usize) -> &[usize] {
+        &self.slots[course]
+    }
+
+    /// A random qualified instructor, suitable room and time for course `course`.
+    pub fn random_gene<R: Rng + ?Sized>(&self, course: usize, rng: &mut R) -> Gene {
+        Gene::new(
+            *self.instructors[course].choose(rng).unwrap(),
+            *self.rooms[course].choose(rng).unwrap(),
+            *self.slots[course].choose(rng).unwrap(),
+        )
+    }
+
+    /// Like [`Candidates::random_gene`], but with a time the instructor
+    /// prefers, or failing that is available at, where there is one.
+    pub fn favoured_gene<R: Rng + ?Sized>(&self, course: usize, rng: &mut R) -> Gene {
+        let pick = rng.random_range(0..self.instructors[course].len());
+        Gene::new(
+            self.instructors[course][pick],
+            *self.rooms[course].choose(rng).unwrap(),
+            *self.favoured[course][pick].choose(rng).unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn favoured_gene_keeps_to_preferred_times() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(1);
+        let ana = problem.instructor_index("Ana").unwrap();
+
+        for _ in 0..200 {
+            let gene = candidates.favoured_gene(0, &mut rng);
+            assert!(candidates.instructors(0).contains(&gene.instructor()));
+            assert!(candidates.rooms(0).contains(&gene.room()));
+            assert!(candidates.slots(0).contains(&gene.slot()));
+            if gene.instructor() == ana {
+                assert!(problem.slot(gene.slot()).1 < testing::time(11, 0));
+            }
+        }
+    }
+}
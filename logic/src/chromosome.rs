@@ -0,0 +1,208 @@
+use std::fmt;
+
+use chrono::Duration;
+
+use crate::conflict::TimeWindow;
+use crate::problem::Problem;
+use crate::schedule::ClassSchedule;
+
+/// The placement of one class as indices into the problem's instructor, room
+/// and slot tables. The class's course is its position in the chromosome, so
+/// gene `i` always places `problem.courses[i]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gene {
+    instructor: u16,
+    room: u16,
+    slot: u16,
+}
+
+impl Gene {
+    /// Indices must be below [`MAX_ENTRIES`](crate::problem::MAX_ENTRIES),
+    /// which [`Problem::validate`] guarantees for a valid problem.
+    pub fn new(instructor: usize, room: usize, slot: usize) -> Self {
+        Self {
+            instructor: instructor as u16,
+            room: room as u16,
+            slot: slot as u16,
+        }
+    }
+
+    pub fn instructor(self) -> usize {
+        usize::from(self.instructor)
+    }
+
+    pub fn room(self) -> usize {
+        usize::from(self.room)
+    }
+
+    /// See [`Problem::slot`].
+    pub fn slot(self) -> usize {
+        usize::from(self.slot)
+    }
+
+    /// When class `index` meets if placed by this gene.
+    pub fn window(self, problem: &Problem, index: usize) -> TimeWindow {
+        let (days, start) = problem.slot(self.slot());
+        TimeWindow {
+            days,
+            start,
+            end: start + Duration::minutes(problem.courses[index].duration),
+        }
+    }
+
+    /// The gene placing `class` as class `index` of `problem`.
+    pub fn encode(problem: &Problem, index: usize, class: &ClassSchedule) -> Result<Self, EncodeError> {
+        let course = &problem.courses[index];
+        if class.course != course.name {
+            return Err(EncodeError::WrongCourse {
+                index,
+                expected: course.name.clone(),
+                found: class.course.clone(),
+            });
+        }
+        let instructor = problem
+            .instructor_index(&class.instructor)
+            .ok_or_else(|| EncodeError::UnknownInstructor(class.instructor.clone()))?;
+        let room = problem
+            .room_index(&class.room)
+            .ok_or_else(|| EncodeError::UnknownRoom(class.room.clone()))?;
+        let slot = problem
+            .slot_index(class.days, class.start_time)
+            .ok_or_else(|| EncodeError::UnknownSlot { course: class.course.clone() })?;
+        Ok(Self::new(instructor, room, slot))
+    }
+
+    /// The readable form of this gene as class `index` of `problem`.
+    pub fn decode(self, problem: &Problem, index: usize) -> ClassSchedule {
+        let course = &problem.courses[index];
+        let (days, start) = problem.slot(self.slot());
+        ClassSchedule::new(
+            &course.name,
+            &problem.instructors[self.instructor()].name,
+            &problem.rooms[self.room()].name,
+            days,
+            start,
+            course.duration,
+        )
+    }
+}
+
+/// Encodes a schedule listing one class per course, in course order.
+pub fn encode(problem: &Problem, schedule: &[ClassSchedule]) -> Result<Vec<Gene>, EncodeError> {
+    if schedule.len() != problem.courses.len() {
+        return Err(EncodeError::WrongLength {
+            expected: problem.courses.len(),
+            found: schedule.len(),
+        });
+    }
+    schedule
+        .iter()
+        .enumerate()
+        .map(|(i, class)| Gene::encode(problem, i, class))
+        .collect()
+}
+
+pub fn decode(problem: &Problem, genes: &[Gene]) -> Vec<ClassSchedule> {
+    genes
+        .iter()
+        .enumerate()
+        .map(|(i, gene)| gene.decode(problem, i))
+        .collect()
+}
+
+/// Why a [`ClassSchedule`] list has no gene encoding for a problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    WrongLength { expected: usize, found: usize },
+    /// Class `index` is not of the problem's `index`th course.
+    WrongCourse { index: usize, expected: String, found: String },
+    UnknownInstructor(String),
+    UnknownRoom(String),
+    /// The class's days and start time are not among the problem's slots.
+    UnknownSlot { course: String },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::WrongLength { expected, found } => {
+                write!(f, "expected {} classes, one per course, found {}", expected, found)
+            }
+            EncodeError::WrongCourse { index, expected, found } => {
+                write!(f, "class {} should be {}, found {}", index, expected, found)
+            }
+            EncodeError::UnknownInstructor(name) => write!(f, "unknown instructor {}", name),
+            EncodeError::UnknownRoom(name) => write!(f, "unknown room {}", name),
+            EncodeError::UnknownSlot { course } => {
+                write!(f, "{} is not at one of the problem's meeting patterns and start times", course)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::candidates::Candidates;
+    use crate::ga::generate_random_schedule;
+    use crate::testing;
+
+    #[test]
+    fn decoding_and_encoding_gives_back_the_genes() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(8);
+        for _ in 0..20 {
+            let genes = generate_random_schedule(&candidates, &mut rng);
+            assert_eq!(encode(&problem, &decode(&problem, &genes)), Ok(genes));
+        }
+    }
+
+    #[test]
+    fn encoding_reports_what_does_not_match_the_problem() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(8);
+        let schedule = decode(&problem, &generate_random_schedule(&candidates, &mut rng));
+        let broken = |change: fn(&mut ClassSchedule)| {
+            let mut schedule = schedule.clone();
+            change(&mut schedule[1]);
+            encode(&problem, &schedule)
+        };
+
+        assert_eq!(
+            encode(&problem, &schedule[1..]),
+            Err(EncodeError::WrongLength {
+                expected: schedule.len(),
+                found: schedule.len() - 1,
+            })
+        );
+        assert_eq!(
+            broken(|class| class.course = "Course 5".to_string()),
+            Err(EncodeError::WrongCourse {
+                index: 1,
+                expected: "Course 1".to_string(),
+                found: "Course 5".to_string(),
+            })
+        );
+        assert_eq!(
+            broken(|class| class.instructor = "Nobody".to_string()),
+            Err(EncodeError::UnknownInstructor("Nobody".to_string()))
+        );
+        assert_eq!(
+            broken(|class| class.room = "Attic".to_string()),
+            Err(EncodeError::UnknownRoom("Attic".to_string()))
+        );
+        assert_eq!(
+            broken(|class| class.start_time = testing::time(7, 15)),
+            Err(EncodeError::UnknownSlot {
+                course: "Course 1".to_string(),
+            })
+        );
+    }
+}
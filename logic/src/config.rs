@@ -0,0 +1,204 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::crossover::CrossoverOperator;
+use crate::island::Topology;
+use crate::selection::SelectionStrategy;
+
+/// Tuning knobs for a solver run.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GaConfig {
+    pub population_size: usize,
+    /// Number of best schedules copied unchanged into the next generation.
+    pub elite_count: usize,
+    pub crossover: CrossoverOperator,
+    /// Probability that two selected parents are recombined rather than cloned.
+    pub crossover_rate: f64,
+    /// Probability that a child is mutated.
+    pub mutation_rate: f64,
+    /// Whether children have their hard conflicts greedily repaired.
+    pub repair: bool,
+    pub selection: SelectionStrategy,
+    pub tournament_size: usize,
+    /// Upper bound on the number of generations to evolve.
+    pub generations: usize,
+    /// Stop as soon as a schedule without hard violations is found, instead
+    /// of only once it is free of soft violations too.
+    pub stop_when_feasible: bool,
+    /// Stop after this many generations without the best schedule improving.
+    pub stagnation_limit: Option<usize>,
+    /// Stop once the run has taken this many milliseconds.
+    pub time_limit_ms: Option<u64>,
+    /// Number of populations of `population_size` schedules evolving side by
+    /// side, exchanging their best schedules every `migration_interval`
+    /// generations.
+    pub islands: usize,
+    pub topology: Topology,
+    pub migration_interval: usize,
+    /// Number of best schedules each island sends per migration.
+    pub migrants: usize,
+    /// Threads used to evaluate fitness, or with several islands to evolve
+    /// them; 0 uses every available core.
+    pub threads: usize,
+    /// Seed for the random number generator. When unset a fresh seed is
+    /// drawn and reported in the solution so the run can be replayed.
+    pub seed: Option<u64>,
+}
+
+impl Default for GaConfig {
+    fn default() -> Self {
+        Self {
+            population_size: 50,
+            elite_count: 2,
+            crossover: CrossoverOperator::SinglePoint,
+            crossover_rate: 0.9,
+            mutation_rate: 0.2,
+            repair: false,
+            selection: SelectionStrategy::Tournament,
+            tournament_size: 3,
+            generations: 100,
+            stop_when_feasible: false,
+            stagnation_limit: None,
+            time_limit_ms: None,
+            islands: 1,
+            topology: Topology::Ring,
+            migration_interval: 10,
+            migrants: 2,
+            threads: 0,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    PopulationTooSmall(usize),
+    TooManyElites { elite_count: usize, population_size: usize },
+    RateOutOfRange { name: &'static str, value: f64 },
+    InvalidTournamentSize { tournament_size: usize, population_size: usize },
+    ZeroStagnationLimit,
+    NoIslands,
+    ZeroMigrationInterval,
+    TooManyMigrants { migrants: usize, population_size: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::PopulationTooSmall(size) => {
+                write!(f, "population size must be at least 2, got {}", size)
+            }
+            ConfigError::TooManyElites { elite_count, population_size } => write!(
+                f,
+                "elite count {} must be smaller than the population size {}",
+                elite_count, population_size
+            ),
+            ConfigError::RateOutOfRange { name, value } => {
+                write!(f, "{} must be between 0 and 1, got {}", name, value)
+            }
+            ConfigError::InvalidTournamentSize { tournament_size, population_size } => write!(
+                f,
+                "tournament size must be between 1 and the population size {}, got {}",
+                population_size, tournament_size
+            ),
+            ConfigError::ZeroStagnationLimit => write!(f, "stagnation limit must be at least 1"),
+            ConfigError::NoIslands => write!(f, "there must be at least one island"),
+            ConfigError::ZeroMigrationInterval => write!(f, "migration interval must be at least 1"),
+            ConfigError::TooManyMigrants { migrants, population_size } => write!(
+                f,
+                "migrant count {} must be smaller than the population size {}",
+                migrants, population_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GaConfig {
+    /// The number of worker threads to actually use.
+    pub fn worker_threads(&self) -> usize {
+        match self.threads {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            threads => threads,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.population_size < 2 {
+            return Err(ConfigError::PopulationTooSmall(self.population_size));
+        }
+        if self.elite_count >= self.population_size {
+            return Err(ConfigError::TooManyElites {
+                elite_count: self.elite_count,
+                population_size: self.population_size,
+            });
+        }
+        let mut rates = vec![("crossover rate", self.crossover_rate), ("mutation rate", self.mutation_rate)];
+        if let SelectionStrategy::Truncation { fraction } = self.selection {
+            rates.push(("truncation fraction", fraction));
+        }
+        for (name, value) in rates {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::RateOutOfRange { name, value });
+            }
+        }
+        if self.tournament_size == 0 || self.tournament_size > self.population_size {
+            return Err(ConfigError::InvalidTournamentSize {
+                tournament_size: self.tournament_size,
+                population_size: self.population_size,
+            });
+        }
+        if self.stagnation_limit == Some(0) {
+            return Err(ConfigError::ZeroStagnationLimit);
+        }
+        if self.islands == 0 {
+            return Err(ConfigError::NoIslands);
+        }
+        if self.migration_interval == 0 {
+            return Err(ConfigError::ZeroMigrationInterval);
+        }
+        if self.migrants >= self.population_size {
+            return Err(ConfigError::TooManyMigrants {
+                migrants: self.migrants,
+                population_size: self.population_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(GaConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_zero_limits_and_intervals() {
+        let zero_stagnation = GaConfig { stagnation_limit: Some(0), ..GaConfig::default() };
+        assert_eq!(zero_stagnation.validate(), Err(ConfigError::ZeroStagnationLimit));
+
+        let zero_interval = GaConfig { migration_interval: 0, ..GaConfig::default() };
+        assert_eq!(zero_interval.validate(), Err(ConfigError::ZeroMigrationInterval));
+
+        let one = GaConfig { stagnation_limit: Some(1), ..GaConfig::default() };
+        assert_eq!(one.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let config: GaConfig = serde_json::from_str(r#"{"generations": 10}"#).unwrap();
+        assert_eq!(config, GaConfig { generations: 10, ..GaConfig::default() });
+
+        let misspelt = serde_json::from_str::<GaConfig>(r#"{"generation": 10}"#).unwrap_err();
+        assert!(misspelt.to_string().contains("unknown field `generation`"));
+    }
+}
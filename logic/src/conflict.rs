@@ -0,0 +1,102 @@
+use std::fmt;
+
+use chrono::NaiveTime;
+use serde::Serialize;
+
+use crate::constraint::Hardness;
+use crate::schedule::Days;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    InstructorClash,
+    RoomClash,
+    UnqualifiedInstructor,
+    RoomCapacity,
+    MissingRoomFeature,
+    InstructorUnavailable,
+    InstructorPreference,
+    DailyLoad,
+    WeeklyLoad,
+    ConsecutiveClasses,
+    GroupClash,
+    StudentClash,
+}
+
+/// The stretch of time during which the involved classes collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimeWindow {
+    pub days: Days,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    /// Whether the two windows share a day and their times overlap.
+    pub fn overlaps(&self, other: &TimeWindow) -> bool {
+        self.days.intersects(other.days) && self.start < other.end && other.start < self.end
+    }
+
+    /// The part of the two windows that coincides.
+    pub fn overlap(&self, other: &TimeWindow) -> Self {
+        Self {
+            days: self.days.intersection(other.days),
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        }
+    }
+}
+
+/// A single broken rule in a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub hardness: Hardness,
+    /// Indices into the schedule of the classes involved.
+    pub classes: Vec<usize>,
+    /// Course names of the classes involved.
+    pub courses: Vec<String>,
+    /// The instructor, room, ... that is over-committed.
+    pub resource: String,
+    pub window: Option<TimeWindow>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let courses = self.courses.join(" and ");
+        match self.kind {
+            ConflictKind::InstructorClash => {
+                write!(f, "Instructor {} has overlapping classes {}", self.resource, courses)?
+            }
+            ConflictKind::RoomClash => write!(f, "Room {} is double-booked for {}", self.resource, courses)?,
+            ConflictKind::UnqualifiedInstructor => {
+                write!(f, "Instructor {} is not qualified to teach {}", self.resource, courses)?
+            }
+            ConflictKind::RoomCapacity => write!(f, "Room {} is too small for {}", self.resource, courses)?,
+            ConflictKind::MissingRoomFeature => {
+                write!(f, "Room {} lacks features required by {}", self.resource, courses)?
+            }
+            ConflictKind::InstructorUnavailable => {
+                write!(f, "Instructor {} is unavailable to teach {}", self.resource, courses)?
+            }
+            ConflictKind::InstructorPreference => {
+                write!(f, "Instructor {} would rather not teach {} at this time", self.resource, courses)?
+            }
+            ConflictKind::DailyLoad => {
+                write!(f, "Instructor {} is over the daily teaching limit with {}", self.resource, courses)?
+            }
+            ConflictKind::WeeklyLoad => {
+                write!(f, "Instructor {} is over the weekly teaching limit with {}", self.resource, courses)?
+            }
+            ConflictKind::ConsecutiveClasses => {
+                write!(f, "Instructor {} teaches too many classes back to back: {}", self.resource, courses)?
+            }
+            ConflictKind::GroupClash => write!(f, "Group {} has overlapping classes {}", self.resource, courses)?,
+            ConflictKind::StudentClash => write!(f, "{} have overlapping classes {}", self.resource, courses)?,
+        }
+        if let Some(window) = &self.window {
+            write!(f, " ({} {}-{})", window.days, window.start.format("%H:%M"), window.end.format("%H:%M"))?;
+        }
+        Ok(())
+    }
+}
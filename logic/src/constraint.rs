@@ -0,0 +1,1012 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::thread;
+
+use chrono::Weekday;
+use serde::Serialize;
+
+use crate::chromosome::Gene;
+use crate::conflict::{Conflict, ConflictKind, TimeWindow};
+use crate::occupancy::Occupancy;
+use crate::problem::{Instructor, LoadLimits, Problem};
+use crate::schedule::Days;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hardness {
+    /// Must hold for the schedule to be usable at all.
+    Hard,
+    /// Desirable, traded off against other soft constraints.
+    Soft,
+}
+
+/// A scheduling rule the fitness model scores schedules against.
+pub trait Constraint: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn hardness(&self) -> Hardness;
+    /// Every place `schedule` breaks this rule.
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict>;
+
+    /// Number of times `schedule` breaks this rule. Override when counting
+    /// is cheaper than building the full [`Conflict`] list.
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        self.conflicts(problem, schedule).len() as u32
+    }
+
+    /// The part of [`Constraint::violations`] that depends on where class
+    /// `index` is placed: moving only that class changes both by the same
+    /// amount, which lets [`FitnessModel::evaluate_move`] skip rescoring
+    /// everything else. Usually the violations the class is involved in, but
+    /// it can be negative for rules where a class may also clear violations.
+    /// The default counts every violation, which is correct for any rule.
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], _index: usize) -> i64 {
+        i64::from(self.violations(problem, schedule))
+    }
+}
+
+/// An instructor teaching two classes at the same time.
+pub struct InstructorClash;
+
+impl Constraint for InstructorClash {
+    fn name(&self) -> &'static str {
+        "instructor clash"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        clash_conflicts(problem, schedule, ConflictKind::InstructorClash, |gene| {
+            &problem.instructors[gene.instructor()].name
+        })
+    }
+
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        let windows = windows(problem, schedule);
+        Occupancy::new(&windows, |i| [schedule[i].instructor()]).clashes(&windows).len() as u32
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        count_involving(problem, schedule, index, |j| schedule[j].instructor() == schedule[index].instructor())
+    }
+}
+
+/// Two classes booked into the same room at the same time.
+pub struct RoomClash;
+
+impl Constraint for RoomClash {
+    fn name(&self) -> &'static str {
+        "room clash"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        clash_conflicts(problem, schedule, ConflictKind::RoomClash, |gene| &problem.rooms[gene.room()].name)
+    }
+
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        let windows = windows(problem, schedule);
+        Occupancy::new(&windows, |i| [schedule[i].room()]).clashes(&windows).len() as u32
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        count_involving(problem, schedule, index, |j| schedule[j].room() == schedule[index].room())
+    }
+}
+
+/// Two overlapping classes taken by the same student group.
+pub struct GroupClash;
+
+impl Constraint for GroupClash {
+    fn name(&self) -> &'static str {
+        "group clash"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let windows = windows(problem, schedule);
+        group_occupancy(problem, &windows)
+            .clashes(&windows)
+            .into_iter()
+            .map(|(i, j)| {
+                let shared: Vec<&str> = problem.courses[i]
+                    .shared_groups(&problem.courses[j])
+                    .map(String::as_str)
+                    .collect();
+                Conflict {
+                    kind: ConflictKind::GroupClash,
+                    hardness: Hardness::Hard,
+                    classes: vec![i, j],
+                    courses: vec![problem.courses[i].name.clone(), problem.courses[j].name.clone()],
+                    resource: shared.join(", "),
+                    window: Some(windows[i].overlap(&windows[j])),
+                }
+            })
+            .collect()
+    }
+
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        let windows = windows(problem, schedule);
+        group_occupancy(problem, &windows).clashes(&windows).len() as u32
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        let course = &problem.courses[index];
+        count_involving(problem, schedule, index, |j| {
+            course.shared_groups(&problem.courses[j]).next().is_some()
+        })
+    }
+}
+
+fn group_occupancy<'a>(problem: &'a Problem, windows: &[TimeWindow]) -> Occupancy<&'a str> {
+    Occupancy::new(windows, |i| problem.courses[i].groups.iter().map(String::as_str))
+}
+
+/// Students enrolled in two courses whose classes overlap. Each student with
+/// at least one clash counts as one violation, however many clashes they
+/// have; conflicts are reported per pair of overlapping courses.
+pub struct StudentClash {
+    /// The pairs `(i, j, students)` with `i < j` of courses sharing at least
+    /// one student, and where those students are in `paired`.
+    pairs: Vec<(usize, usize, Range<usize>)>,
+    /// The students shared by each pair of courses, by index, back to back.
+    paired: Vec<usize>,
+    /// The courses each student is enrolled in, by index.
+    enrollments: Vec<Vec<usize>>,
+    /// The students enrolled in each course, by index.
+    attendees: Vec<Vec<usize>>,
+}
+
+impl StudentClash {
+    /// Precomputes who is enrolled in what, and which students every pair
+    /// of courses has in common.
+    pub fn new(problem: &Problem) -> Self {
+        let index: HashMap<&str, usize> = problem
+            .courses
+            .iter()
+            .enumerate()
+            .map(|(i, course)| (course.name.as_str(), i))
+            .collect();
+
+        let mut shared: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        let mut enrollments = Vec::with_capacity(problem.students.len());
+        let mut attendees = vec![Vec::new(); problem.courses.len()];
+        for (s, student) in problem.students.iter().enumerate() {
+            let mut enrolled: Vec<usize> = student.courses.iter().filter_map(|c| index.get(c.as_str()).copied()).collect();
+            enrolled.sort_unstable();
+            enrolled.dedup();
+            for (k, &i) in enrolled.iter().enumerate() {
+                attendees[i].push(s);
+                for &j in &enrolled[k + 1..] {
+                    shared.entry((i, j)).or_default().push(s);
+                }
+            }
+            enrollments.push(enrolled);
+        }
+
+        let mut pairs = Vec::with_capacity(shared.len());
+        let mut paired = Vec::new();
+        for ((i, j), students) in shared {
+            pairs.push((i, j, paired.len()..paired.len() + students.len()));
+            paired.extend(students);
+        }
+
+        Self {
+            pairs,
+            paired,
+            enrollments,
+            attendees,
+        }
+    }
+
+    /// Whether any two of `student`'s courses overlap, given when course `i`
+    /// meets as `window(i)`.
+    fn has_clash(&self, student: usize, window: impl Fn(usize) -> TimeWindow) -> bool {
+        let enrolled = &self.enrollments[student];
+        enrolled.iter().enumerate().any(|(k, &i)| {
+            let first = window(i);
+            enrolled[k + 1..].iter().any(|&j| first.overlaps(&window(j)))
+        })
+    }
+}
+
+impl Constraint for StudentClash {
+    fn name(&self) -> &'static str {
+        "student clash"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Soft
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let windows = windows(problem, schedule);
+        self.pairs
+            .iter()
+            .filter(|(i, j, _)| windows[*i].overlaps(&windows[*j]))
+            .map(|(i, j, students)| Conflict {
+                kind: ConflictKind::StudentClash,
+                hardness: Hardness::Soft,
+                classes: vec![*i, *j],
+                courses: vec![problem.courses[*i].name.clone(), problem.courses[*j].name.clone()],
+                resource: match students.len() {
+                    1 => "1 student".to_string(),
+                    n => format!("{} students", n),
+                },
+                window: Some(windows[*i].overlap(&windows[*j])),
+            })
+            .collect()
+    }
+
+    /// Only the students of overlapping pairs of courses can have a clash.
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        let windows = windows(problem, schedule);
+        let mut clashing = vec![false; self.enrollments.len()];
+        let mut count = 0;
+        for (_, _, students) in self.pairs.iter().filter(|(i, j, _)| windows[*i].overlaps(&windows[*j])) {
+            for &student in &self.paired[students.clone()] {
+                if !clashing[student] {
+                    clashing[student] = true;
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Only the students taking the moved class can gain or lose a clash.
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        self.attendees[index]
+            .iter()
+            .filter(|&&student| self.has_clash(student, |i| schedule[i].window(problem, i)))
+            .count() as i64
+    }
+}
+
+/// A class taught by an instructor who is not qualified for its course.
+pub struct UnqualifiedInstructor;
+
+impl Constraint for UnqualifiedInstructor {
+    fn name(&self) -> &'static str {
+        "unqualified instructor"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let unqualified = |i, gene| self.broken_by(problem, i, gene);
+        class_conflicts(problem, schedule, ConflictKind::UnqualifiedInstructor, Hardness::Hard, unqualified, |_, gene| {
+            (&problem.instructors[gene.instructor()].name, None)
+        })
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        i64::from(self.broken_by(problem, index, schedule[index]))
+    }
+}
+
+impl UnqualifiedInstructor {
+    fn broken_by(&self, problem: &Problem, index: usize, gene: Gene) -> bool {
+        !problem.instructors[gene.instructor()].can_teach(&problem.courses[index])
+    }
+}
+
+/// A class whose enrollment exceeds the capacity of its room.
+pub struct RoomCapacity;
+
+impl Constraint for RoomCapacity {
+    fn name(&self) -> &'static str {
+        "room capacity"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let too_small = |i, gene| self.broken_by(problem, i, gene);
+        class_conflicts(problem, schedule, ConflictKind::RoomCapacity, Hardness::Hard, too_small, |_, gene| {
+            (&problem.rooms[gene.room()].name, None)
+        })
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        i64::from(self.broken_by(problem, index, schedule[index]))
+    }
+}
+
+impl RoomCapacity {
+    fn broken_by(&self, problem: &Problem, index: usize, gene: Gene) -> bool {
+        !problem.rooms[gene.room()].fits(&problem.courses[index])
+    }
+}
+
+/// A class placed in a room without every feature its course requires.
+pub struct RoomFeatures;
+
+impl Constraint for RoomFeatures {
+    fn name(&self) -> &'static str {
+        "room features"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let missing = |i, gene| self.broken_by(problem, i, gene);
+        class_conflicts(problem, schedule, ConflictKind::MissingRoomFeature, Hardness::Hard, missing, |_, gene| {
+            (&problem.rooms[gene.room()].name, None)
+        })
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        i64::from(self.broken_by(problem, index, schedule[index]))
+    }
+}
+
+impl RoomFeatures {
+    fn broken_by(&self, problem: &Problem, index: usize, gene: Gene) -> bool {
+        !problem.rooms[gene.room()].provides(&problem.courses[index])
+    }
+}
+
+/// A class scheduled outside its instructor's availability.
+pub struct InstructorAvailability;
+
+impl Constraint for InstructorAvailability {
+    fn name(&self) -> &'static str {
+        "instructor availability"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let unavailable = |i, gene| self.broken_by(problem, i, gene);
+        class_conflicts(problem, schedule, ConflictKind::InstructorUnavailable, Hardness::Hard, unavailable, |i, gene| {
+            (&problem.instructors[gene.instructor()].name, Some(gene.window(problem, i)))
+        })
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        i64::from(self.broken_by(problem, index, schedule[index]))
+    }
+}
+
+impl InstructorAvailability {
+    fn broken_by(&self, problem: &Problem, index: usize, gene: Gene) -> bool {
+        let window = gene.window(problem, index);
+        !problem.instructors[gene.instructor()].is_available(window.days, window.start, window.end)
+    }
+}
+
+/// A class scheduled outside the times its instructor prefers.
+pub struct InstructorPreference;
+
+impl Constraint for InstructorPreference {
+    fn name(&self) -> &'static str {
+        "instructor preference"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Soft
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let unpreferred = |i, gene| self.broken_by(problem, i, gene);
+        class_conflicts(problem, schedule, ConflictKind::InstructorPreference, Hardness::Soft, unpreferred, |i, gene| {
+            (&problem.instructors[gene.instructor()].name, Some(gene.window(problem, i)))
+        })
+    }
+
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        i64::from(self.broken_by(problem, index, schedule[index]))
+    }
+}
+
+impl InstructorPreference {
+    fn broken_by(&self, problem: &Problem, index: usize, gene: Gene) -> bool {
+        let window = gene.window(problem, index);
+        !problem.instructors[gene.instructor()].prefers(window.days, window.start, window.end)
+    }
+}
+
+/// Classes separated by at most this many minutes count as back to back.
+const CONSECUTIVE_GAP_MINUTES: i64 = 15;
+
+/// An instructor teaching more per day or per week, or more classes in a
+/// row, than their [`LoadLimits`](crate::problem::LoadLimits) allow.
+pub struct TeachingLoad;
+
+impl Constraint for TeachingLoad {
+    fn name(&self) -> &'static str {
+        "teaching load"
+    }
+
+    fn hardness(&self) -> Hardness {
+        Hardness::Hard
+    }
+
+    fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        let windows = windows(problem, schedule);
+        let mut conflicts = Vec::new();
+        for (instructor, classes) in classes_by_instructor(problem, schedule).into_iter().enumerate() {
+            let Instructor { name, limits, .. } = &problem.instructors[instructor];
+            let taught: Vec<TimeWindow> = classes.iter().map(|&i| windows[i]).collect();
+            check_load(limits, &taught, |kind, meetings, window| {
+                let classes: Vec<usize> = meetings.iter().map(|&k| classes[k]).collect();
+                conflicts.push(Conflict {
+                    kind,
+                    hardness: Hardness::Hard,
+                    courses: classes.iter().map(|&i| problem.courses[i].name.clone()).collect(),
+                    classes,
+                    resource: name.clone(),
+                    window,
+                })
+            });
+        }
+        conflicts
+    }
+
+    fn violations(&self, problem: &Problem, schedule: &[Gene]) -> u32 {
+        let windows = windows(problem, schedule);
+        classes_by_instructor(problem, schedule)
+            .into_iter()
+            .enumerate()
+            .map(|(instructor, classes)| {
+                let taught: Vec<TimeWindow> = classes.iter().map(|&i| windows[i]).collect();
+                load_violations(&problem.instructors[instructor].limits, &taught)
+            })
+            .sum()
+    }
+
+    /// How many more limits the moved class's instructor breaks with the
+    /// class than without it. Nobody else's load depends on the class, and
+    /// this can be negative when the class joins two over-long runs of
+    /// back-to-back classes into one.
+    fn class_violations(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        let instructor = schedule[index].instructor();
+        let limits = &problem.instructors[instructor].limits;
+        let taught: Vec<TimeWindow> = (0..schedule.len())
+            .filter(|&i| i != index && schedule[i].instructor() == instructor)
+            .map(|i| schedule[i].window(problem, i))
+            .collect();
+        let without = load_violations(limits, &taught);
+        let mut taught = taught;
+        taught.push(schedule[index].window(problem, index));
+        i64::from(load_violations(limits, &taught)) - i64::from(without)
+    }
+}
+
+/// The classes of `schedule` each instructor teaches, by instructor index.
+fn classes_by_instructor(problem: &Problem, schedule: &[Gene]) -> Vec<Vec<usize>> {
+    let mut classes = vec![Vec::new(); problem.instructors.len()];
+    for (i, gene) in schedule.iter().enumerate() {
+        classes[gene.instructor()].push(i);
+    }
+    classes
+}
+
+/// Number of `limits` broken by an instructor teaching classes that meet at
+/// `windows`.
+fn load_violations(limits: &LoadLimits, windows: &[TimeWindow]) -> u32 {
+    let mut violations = 0;
+    check_load(limits, windows, |_, _, _| violations += 1);
+    violations
+}
+
+/// Calls `report` for every one of `limits` broken by an instructor teaching
+/// classes that meet at `windows`, with the positions in `windows` of the
+/// classes involved and, for daily limits, the day they fall on.
+fn check_load(limits: &LoadLimits, windows: &[TimeWindow], mut report: impl FnMut(ConflictKind, &[usize], Option<TimeWindow>)) {
+    if windows.is_empty() {
+        return;
+    }
+    let weekly: i64 = windows.iter().map(weekly_minutes).sum();
+    if limits.max_weekly_minutes.is_some_and(|max| weekly > max) {
+        let all: Vec<usize> = (0..windows.len()).collect();
+        report(ConflictKind::WeeklyLoad, &all, None);
+    }
+
+    let mut meetings = Vec::with_capacity(windows.len());
+    for day in Days::ALL.iter() {
+        meetings.clear();
+        meetings.extend((0..windows.len()).filter(|&k| windows[k].days.contains(day)));
+        if meetings.is_empty() {
+            continue;
+        }
+        meetings.sort_by_key(|&k| windows[k].start);
+
+        let daily: i64 = meetings.iter().map(|&k| meeting_minutes(&windows[k])).sum();
+        if limits.max_daily_minutes.is_some_and(|max| daily > max) {
+            report(ConflictKind::DailyLoad, &meetings, Some(day_window(windows, &meetings, day)));
+        }
+
+        if let Some(max) = limits.max_consecutive {
+            // Runs of back-to-back classes, in meetings sorted by start time.
+            let runs = meetings.chunk_by(|&a, &b| (windows[b].start - windows[a].end).num_minutes() <= CONSECUTIVE_GAP_MINUTES);
+            for run in runs.filter(|run| run.len() > max) {
+                report(ConflictKind::ConsecutiveClasses, run, Some(day_window(windows, run, day)));
+            }
+        }
+    }
+}
+
+fn meeting_minutes(window: &TimeWindow) -> i64 {
+    (window.end - window.start).num_minutes()
+}
+
+fn weekly_minutes(window: &TimeWindow) -> i64 {
+    meeting_minutes(window) * window.days.len() as i64
+}
+
+fn day_window(windows: &[TimeWindow], meetings: &[usize], day: Weekday) -> TimeWindow {
+    TimeWindow {
+        days: Days::from_weekdays(&[day]),
+        start: meetings.iter().map(|&i| windows[i].start).min().unwrap(),
+        end: meetings.iter().map(|&i| windows[i].end).max().unwrap(),
+    }
+}
+
+/// When each class of `schedule` meets.
+fn windows(problem: &Problem, schedule: &[Gene]) -> Vec<TimeWindow> {
+    schedule
+        .iter()
+        .enumerate()
+        .map(|(i, gene)| gene.window(problem, i))
+        .collect()
+}
+
+/// One conflict per class for which `broken` holds; `describe` names the
+/// resource involved and, where it matters, when.
+fn class_conflicts<'a>(
+    problem: &'a Problem,
+    schedule: &[Gene],
+    kind: ConflictKind,
+    hardness: Hardness,
+    broken: impl Fn(usize, Gene) -> bool,
+    describe: impl Fn(usize, Gene) -> (&'a String, Option<TimeWindow>),
+) -> Vec<Conflict> {
+    schedule
+        .iter()
+        .enumerate()
+        .filter(|&(i, &gene)| broken(i, gene))
+        .map(|(i, &gene)| {
+            let (resource, window) = describe(i, gene);
+            Conflict {
+                kind,
+                hardness,
+                classes: vec![i],
+                courses: vec![problem.courses[i].name.clone()],
+                resource: resource.clone(),
+                window,
+            }
+        })
+        .collect()
+}
+
+/// Number of other classes overlapping class `index` for which `related`
+/// holds.
+fn count_involving(problem: &Problem, schedule: &[Gene], index: usize, related: impl Fn(usize) -> bool) -> i64 {
+    let window = schedule[index].window(problem, index);
+    (0..schedule.len())
+        .filter(|&j| j != index && related(j) && window.overlaps(&schedule[j].window(problem, j)))
+        .count() as i64
+}
+
+fn clash_conflicts<'a>(
+    problem: &'a Problem,
+    schedule: &[Gene],
+    kind: ConflictKind,
+    resource: impl Fn(Gene) -> &'a String,
+) -> Vec<Conflict> {
+    let windows = windows(problem, schedule);
+    Occupancy::new(&windows, |i| [resource(schedule[i])])
+        .clashes(&windows)
+        .into_iter()
+        .map(|(i, j)| Conflict {
+            kind,
+            hardness: Hardness::Hard,
+            classes: vec![i, j],
+            courses: vec![problem.courses[i].name.clone(), problem.courses[j].name.clone()],
+            resource: resource(schedule[i]).clone(),
+            window: Some(windows[i].overlap(&windows[j])),
+        })
+        .collect()
+}
+
+/// Fewest genes worth handing to a thread of their own; below this, starting
+/// the thread costs more than scoring them sequentially.
+const MIN_GENES_PER_THREAD: usize = 2_000;
+
+/// How many of `threads` are worth starting for work on `genes` genes.
+pub(crate) fn useful_threads(threads: usize, genes: usize) -> usize {
+    threads.min(genes / MIN_GENES_PER_THREAD).max(1)
+}
+
+/// How one constraint contributed to a [`Fitness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintScore {
+    pub name: &'static str,
+    pub hardness: Hardness,
+    pub violations: u32,
+    pub weight: u32,
+}
+
+impl ConstraintScore {
+    pub fn penalty(&self) -> u64 {
+        u64::from(self.violations) * u64::from(self.weight)
+    }
+}
+
+/// Weighted penalties of a schedule, lower is better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fitness {
+    pub hard: u64,
+    pub soft: u64,
+    pub breakdown: Vec<ConstraintScore>,
+}
+
+/// How many soft penalty points one hard penalty point is worth when the two
+/// have to be folded into a single number.
+pub const HARD_PENALTY_FACTOR: u64 = 1_000;
+
+impl Fitness {
+    fn from_scores(breakdown: Vec<ConstraintScore>) -> Self {
+        let mut fitness = Fitness { hard: 0, soft: 0, breakdown: Vec::new() };
+        for score in &breakdown {
+            match score.hardness {
+                Hardness::Hard => fitness.hard = fitness.hard.saturating_add(score.penalty()),
+                Hardness::Soft => fitness.soft = fitness.soft.saturating_add(score.penalty()),
+            }
+        }
+        fitness.breakdown = breakdown;
+        fitness
+    }
+
+    pub fn is_feasible(&self) -> bool {
+        self.hard == 0
+    }
+
+    /// Hard and soft penalties combined into one number, for strategies that
+    /// need a magnitude rather than an ordering. Saturates at `u64::MAX`.
+    pub fn penalty(&self) -> u64 {
+        self.hard.saturating_mul(HARD_PENALTY_FACTOR).saturating_add(self.soft)
+    }
+
+    /// Ordering key: any hard penalty outweighs every soft penalty.
+    pub fn key(&self) -> (u64, u64) {
+        (self.hard, self.soft)
+    }
+}
+
+/// The set of weighted constraints a schedule is scored against.
+pub struct FitnessModel {
+    constraints: Vec<(Box<dyn Constraint>, u32)>,
+}
+
+impl FitnessModel {
+    /// A model without any constraints; every schedule scores zero.
+    pub fn empty() -> Self {
+        Self { constraints: Vec::new() }
+    }
+
+    pub fn with(mut self, constraint: impl Constraint + 'static, weight: u32) -> Self {
+        self.constraints.push((Box::new(constraint), weight));
+        self
+    }
+
+    /// The default constraints plus those that need data precomputed from
+    /// `problem`, such as student enrollments.
+    pub fn for_problem(problem: &Problem) -> Self {
+        let model = Self::default();
+        if problem.students.is_empty() {
+            model
+        } else {
+            model.with(StudentClash::new(problem), 1)
+        }
+    }
+
+    pub fn evaluate(&self, problem: &Problem, schedule: &[Gene]) -> Fitness {
+        Fitness::from_scores(
+            self.constraints
+                .iter()
+                .map(|(constraint, weight)| ConstraintScore {
+                    name: constraint.name(),
+                    hardness: constraint.hardness(),
+                    violations: constraint.violations(problem, schedule),
+                    weight: *weight,
+                })
+                .collect(),
+        )
+    }
+
+    /// Puts `gene` in place of gene `index` and returns the new fitness of
+    /// `schedule`, given its `fitness` before the move. Only the violations
+    /// the moved class is involved in are recounted.
+    pub fn evaluate_move(
+        &self,
+        problem: &Problem,
+        schedule: &mut [Gene],
+        fitness: &Fitness,
+        index: usize,
+        gene: Gene,
+    ) -> Fitness {
+        let before: Vec<i64> = self
+            .constraints
+            .iter()
+            .map(|(constraint, _)| constraint.class_violations(problem, schedule, index))
+            .collect();
+        schedule[index] = gene;
+
+        Fitness::from_scores(
+            self.constraints
+                .iter()
+                .zip(&fitness.breakdown)
+                .zip(before)
+                .map(|(((constraint, _), score), before)| {
+                    let after = constraint.class_violations(problem, schedule, index);
+                    ConstraintScore {
+                        violations: (i64::from(score.violations) + after - before) as u32,
+                        ..score.clone()
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// The weighted hard violations that depend on where class `index` is
+    /// placed, see [`Constraint::class_violations`]. Between two placements of
+    /// the class, this changes by as much as the hard penalty.
+    pub fn hard_class_penalty(&self, problem: &Problem, schedule: &[Gene], index: usize) -> i64 {
+        self.constraints
+            .iter()
+            .filter(|(constraint, _)| constraint.hardness() == Hardness::Hard)
+            .map(|(constraint, weight)| constraint.class_violations(problem, schedule, index) * i64::from(*weight))
+            .sum()
+    }
+
+    /// Scores every schedule, spreading the work over up to `threads` threads.
+    /// The result is in the same order as `schedules`.
+    pub fn evaluate_all<S>(&self, problem: &Problem, schedules: &[S], threads: usize) -> Vec<Fitness>
+    where
+        S: AsRef<[Gene]> + Sync,
+    {
+        let genes = schedules.iter().map(|schedule| schedule.as_ref().len()).sum();
+        let threads = useful_threads(threads, genes);
+        if threads <= 1 || schedules.len() < 2 {
+            return schedules.iter().map(|schedule| self.evaluate(problem, schedule.as_ref())).collect();
+        }
+
+        let chunk_size = schedules.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let workers: Vec<_> = schedules
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|schedule| self.evaluate(problem, schedule.as_ref()))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Every rule `schedule` breaks, across all constraints in the model.
+    pub fn conflicts(&self, problem: &Problem, schedule: &[Gene]) -> Vec<Conflict> {
+        self.constraints
+            .iter()
+            .flat_map(|(constraint, _)| constraint.conflicts(problem, schedule))
+            .collect()
+    }
+
+    /// The classes involved in at least one hard conflict, in ascending
+    /// order. Soft constraints are not consulted.
+    pub fn hard_conflicted(&self, problem: &Problem, schedule: &[Gene]) -> Vec<usize> {
+        let mut classes: Vec<usize> = self
+            .constraints
+            .iter()
+            .filter(|(constraint, _)| constraint.hardness() == Hardness::Hard)
+            .flat_map(|(constraint, _)| constraint.conflicts(problem, schedule))
+            .flat_map(|conflict| conflict.classes)
+            .collect();
+        classes.sort_unstable();
+        classes.dedup();
+        classes
+    }
+}
+
+impl Default for FitnessModel {
+    /// The built-in constraints, each weighted 1. These do not look at
+    /// `problem.students`; use [`FitnessModel::for_problem`] to keep
+    /// students' courses apart too.
+    fn default() -> Self {
+        Self::empty()
+            .with(InstructorClash, 1)
+            .with(RoomClash, 1)
+            .with(GroupClash, 1)
+            .with(UnqualifiedInstructor, 1)
+            .with(RoomCapacity, 1)
+            .with(RoomFeatures, 1)
+            .with(InstructorAvailability, 1)
+            .with(InstructorPreference, 1)
+            .with(TeachingLoad, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::candidates::Candidates;
+    use crate::problem::{Course, Instructor, LoadLimits, Room, Student};
+    use crate::testing::{self, time};
+
+    /// One instructor with `limits` teaching a 60-minute MWF class of each
+    /// course, starting at `starts`.
+    fn teaching_load_conflicts(limits: LoadLimits, starts: &[NaiveTime]) -> Vec<Conflict> {
+        let names: Vec<String> = (0..starts.len()).map(|i| format!("Course {}", i)).collect();
+        let courses = names.iter().map(|name| Course::new(name, 60, 180)).collect();
+        let qualified: Vec<&str> = names.iter().map(String::as_str).collect();
+        let instructors = vec![Instructor::new("Teacher", &qualified).with_limits(limits)];
+        let problem = Problem::new(courses, instructors, vec![Room::new("Room", 30)], &[Days::MWF], starts);
+        // With a single pattern, slot `i` is the `i`th start time.
+        let schedule: Vec<Gene> = (0..starts.len()).map(|i| Gene::new(0, 0, i)).collect();
+        TeachingLoad.conflicts(&problem, &schedule)
+    }
+
+    #[test]
+    fn teaching_load_reports_days_over_the_daily_limit() {
+        let limits = LoadLimits {
+            max_daily_minutes: Some(120),
+            ..LoadLimits::default()
+        };
+        assert!(teaching_load_conflicts(limits, &[time(8, 0), time(13, 0)]).is_empty());
+
+        let conflicts = teaching_load_conflicts(limits, &[time(8, 0), time(10, 0), time(13, 0)]);
+        assert_eq!(conflicts.len(), 3, "one per meeting day");
+        for conflict in &conflicts {
+            assert_eq!(conflict.kind, ConflictKind::DailyLoad);
+            assert_eq!(conflict.classes, [0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn teaching_load_reports_weeks_over_the_weekly_limit() {
+        let limits = LoadLimits {
+            max_weekly_minutes: Some(500),
+            ..LoadLimits::default()
+        };
+        assert!(teaching_load_conflicts(limits, &[time(8, 0), time(13, 0)]).is_empty());
+
+        let conflicts = teaching_load_conflicts(limits, &[time(8, 0), time(10, 0), time(13, 0)]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::WeeklyLoad);
+        assert_eq!(conflicts[0].classes, [0, 1, 2]);
+        assert_eq!(conflicts[0].window, None);
+    }
+
+    #[test]
+    fn teaching_load_reports_runs_of_back_to_back_classes() {
+        let limits = LoadLimits {
+            max_consecutive: Some(2),
+            ..LoadLimits::default()
+        };
+        // A 15-minute gap still counts as back to back, 16 minutes does not.
+        let conflicts = teaching_load_conflicts(limits, &[time(8, 0), time(9, 15), time(10, 30)]);
+        assert_eq!(conflicts.len(), 3, "one per meeting day");
+        for conflict in &conflicts {
+            assert_eq!(conflict.kind, ConflictKind::ConsecutiveClasses);
+            assert_eq!(conflict.classes, [0, 1, 2]);
+        }
+        assert!(teaching_load_conflicts(limits, &[time(8, 0), time(9, 15), time(10, 31)]).is_empty());
+    }
+
+    #[test]
+    fn student_clash_counts_each_student_once() {
+        let courses = vec![
+            Course::new("A", 60, 180),
+            Course::new("B", 60, 180),
+            Course::new("C", 60, 180),
+            Course::new("D", 60, 180),
+        ];
+        let instructors = vec![Instructor::new("Teacher", &["A", "B", "C", "D"])];
+        let rooms = vec![Room::new("Room", 30)];
+        let students = vec![
+            Student::new("all three", &["A", "B", "C"]),
+            Student::new("two", &["A", "B"]),
+            Student::new("apart", &["C", "D"]),
+        ];
+        let problem = Problem::new(courses, instructors, rooms, &[Days::MWF], &[time(8, 0), time(10, 0)])
+            .with_students(students);
+        let rule = StudentClash::new(&problem);
+
+        // A, B and C at 08:00, D at 10:00.
+        let schedule = [Gene::new(0, 0, 0), Gene::new(0, 0, 0), Gene::new(0, 0, 0), Gene::new(0, 0, 1)];
+        assert_eq!(rule.violations(&problem, &schedule), 2);
+        assert_eq!(rule.conflicts(&problem, &schedule).len(), 3);
+        assert_eq!(rule.conflicts(&problem, &schedule)[0].resource, "2 students");
+        assert_eq!(rule.class_violations(&problem, &schedule, 0), 2);
+        assert_eq!(rule.class_violations(&problem, &schedule, 3), 0);
+    }
+
+    #[test]
+    fn large_penalties_saturate_instead_of_overflowing() {
+        let score = |hardness| ConstraintScore {
+            name: "heavy",
+            hardness,
+            violations: u32::MAX,
+            weight: u32::MAX,
+        };
+        assert_eq!(score(Hardness::Hard).penalty(), u64::from(u32::MAX) * u64::from(u32::MAX));
+
+        let fitness = Fitness::from_scores(vec![score(Hardness::Hard), score(Hardness::Hard), score(Hardness::Soft)]);
+        assert_eq!(fitness.hard, u64::MAX);
+        assert_eq!(fitness.soft, u64::from(u32::MAX) * u64::from(u32::MAX));
+        assert_eq!(fitness.penalty(), u64::MAX);
+        assert!(Fitness::from_scores(vec![score(Hardness::Soft)]).key() < fitness.key());
+    }
+
+    #[test]
+    fn parallel_evaluation_matches_sequential() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let model = FitnessModel::for_problem(&problem);
+        let mut rng = StdRng::seed_from_u64(5);
+        // Enough genes for several threads to be worth starting.
+        let schedules: Vec<Vec<Gene>> = (0..(4 * MIN_GENES_PER_THREAD).div_ceil(problem.courses.len()))
+            .map(|_| crate::ga::generate_random_schedule(&candidates, &mut rng))
+            .collect();
+        assert_eq!(useful_threads(4, schedules.len() * problem.courses.len()), 4);
+
+        assert_eq!(model.evaluate_all(&problem, &schedules, 4), model.evaluate_all(&problem, &schedules, 1));
+    }
+
+    #[test]
+    fn evaluate_move_matches_full_evaluation() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let model = FitnessModel::for_problem(&problem);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut schedule = crate::ga::generate_random_schedule(&candidates, &mut rng);
+        let mut fitness = model.evaluate(&problem, &schedule);
+
+        for _ in 0..2_000 {
+            let (index, gene) = crate::ga::random_move(&schedule, &candidates, &mut rng);
+            fitness = model.evaluate_move(&problem, &mut schedule, &fitness, index, gene);
+            assert_eq!(fitness, model.evaluate(&problem, &schedule));
+        }
+    }
+
+    #[test]
+    fn teaching_load_counts_the_conflicts_it_reports() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..200 {
+            let schedule = crate::ga::generate_random_schedule(&candidates, &mut rng);
+            let conflicts = TeachingLoad.conflicts(&problem, &schedule);
+            assert_eq!(TeachingLoad.violations(&problem, &schedule), conflicts.len() as u32);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::chromosome::Gene;
+use crate::constraint::FitnessModel;
+use crate::problem::Problem;
+
+/// How two parent schedules are recombined into a child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossoverOperator {
+    SinglePoint,
+    TwoPoint,
+    Uniform,
+    ResourceAware,
+}
+
+impl CrossoverOperator {
+    /// Recombines the two parents. For [`CrossoverOperator::ResourceAware`]
+    /// this scores both parents first; the solver instead works out which
+    /// classes are conflicted once per generation and calls
+    /// [`resource_aware`] itself.
+    pub fn apply<R: Rng + ?Sized>(
+        self,
+        problem: &Problem,
+        model: &FitnessModel,
+        parent1: &[Gene],
+        parent2: &[Gene],
+        rng: &mut R,
+    ) -> Vec<Gene> {
+        match self {
+            CrossoverOperator::SinglePoint => single_point(parent1, parent2, rng),
+            CrossoverOperator::TwoPoint => two_point(parent1, parent2, rng),
+            CrossoverOperator::Uniform => uniform(parent1, parent2, rng),
+            CrossoverOperator::ResourceAware => resource_aware(
+                parent1,
+                &model.hard_conflicted(problem, parent1),
+                parent2,
+                &model.hard_conflicted(problem, parent2),
+                rng,
+            ),
+        }
+    }
+}
+
+/// Classes before a random point come from `parent1`, the rest from `parent2`.
+pub fn single_point<R: Rng + ?Sized>(parent1: &[Gene], parent2: &[Gene], rng: &mut R) -> Vec<Gene> {
+    let crossover_point = rng.random_range(0..parent1.len());
+
+    let mut child = Vec::new();
+    child.extend_from_slice(&parent1[..crossover_point]);
+    child.extend_from_slice(&parent2[crossover_point..]);
+
+    child
+}
+
+/// Classes between two random points come from `parent2`, the rest from
+/// `parent1`.
+pub fn two_point<R: Rng + ?Sized>(parent1: &[Gene], parent2: &[Gene], rng: &mut R) -> Vec<Gene> {
+    let a = rng.random_range(0..=parent1.len());
+    let b = rng.random_range(0..=parent1.len());
+    let (start, end) = (a.min(b), a.max(b));
+
+    let mut child = Vec::new();
+    child.extend_from_slice(&parent1[..start]);
+    child.extend_from_slice(&parent2[start..end]);
+    child.extend_from_slice(&parent1[end..]);
+
+    child
+}
+
+/// Each class comes from either parent with equal chance.
+pub fn uniform<R: Rng + ?Sized>(parent1: &[Gene], parent2: &[Gene], rng: &mut R) -> Vec<Gene> {
+    parent1
+        .iter()
+        .zip(parent2)
+        .map(|(class1, class2)| *if rng.random_bool(0.5) { class1 } else { class2 })
+        .collect()
+}
+
+/// Like [`uniform`], but a class that is free of hard conflicts in one parent
+/// and not the other is always taken from the parent where it is
+/// conflict-free. `conflicted1` and `conflicted2` list, in ascending order,
+/// the classes of each parent involved in hard conflicts, as given by
+/// [`FitnessModel::hard_conflicted`].
+pub fn resource_aware<R: Rng + ?Sized>(
+    parent1: &[Gene],
+    conflicted1: &[usize],
+    parent2: &[Gene],
+    conflicted2: &[usize],
+    rng: &mut R,
+) -> Vec<Gene> {
+    parent1
+        .iter()
+        .zip(parent2)
+        .enumerate()
+        .map(|(i, (class1, class2))| {
+            let take_first = match (conflicted1.binary_search(&i).is_ok(), conflicted2.binary_search(&i).is_ok()) {
+                (false, true) => true,
+                (true, false) => false,
+                _ => rng.random_bool(0.5),
+            };
+            *if take_first { class1 } else { class2 }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::candidates::Candidates;
+    use crate::constraint::Hardness;
+    use crate::ga::generate_random_schedule;
+    use crate::testing;
+
+    const CLASSES: usize = 12;
+
+    /// Two parents no gene of which is alike.
+    fn parents() -> (Vec<Gene>, Vec<Gene>) {
+        let parent1 = (0..CLASSES).map(|i| Gene::new(0, 0, i)).collect();
+        let parent2 = (0..CLASSES).map(|i| Gene::new(1, 1, i)).collect();
+        (parent1, parent2)
+    }
+
+    /// For every gene of `child`, whether it came from `parent1`.
+    fn origins(child: &[Gene], parent1: &[Gene], parent2: &[Gene]) -> Vec<bool> {
+        child
+            .iter()
+            .enumerate()
+            .map(|(i, gene)| {
+                assert!(*gene == parent1[i] || *gene == parent2[i], "gene {} comes from neither parent", i);
+                *gene == parent1[i]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_point_takes_a_prefix_of_the_first_parent() {
+        let (parent1, parent2) = parents();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let child = single_point(&parent1, &parent2, &mut rng);
+            let from_first = origins(&child, &parent1, &parent2);
+            let point = from_first.iter().take_while(|&&first| first).count();
+            assert!(from_first[point..].iter().all(|&first| !first));
+        }
+    }
+
+    #[test]
+    fn two_point_takes_a_middle_of_the_second_parent() {
+        let (parent1, parent2) = parents();
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            let child = two_point(&parent1, &parent2, &mut rng);
+            let from_first = origins(&child, &parent1, &parent2);
+            let start = from_first.iter().take_while(|&&first| first).count();
+            let end = start + from_first[start..].iter().take_while(|&&first| !first).count();
+            assert!(from_first[end..].iter().all(|&first| first));
+        }
+    }
+
+    #[test]
+    fn uniform_takes_each_gene_from_either_parent() {
+        let (parent1, parent2) = parents();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut taken = [[false; 2]; CLASSES];
+        for _ in 0..100 {
+            let child = uniform(&parent1, &parent2, &mut rng);
+            for (i, first) in origins(&child, &parent1, &parent2).into_iter().enumerate() {
+                taken[i][usize::from(first)] = true;
+            }
+        }
+        assert!(taken.iter().all(|&[second, first]| first && second));
+    }
+
+    #[test]
+    fn resource_aware_keeps_the_conflict_free_gene() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let model = FitnessModel::for_problem(&problem);
+        let mut rng = StdRng::seed_from_u64(4);
+        let hard_conflicts = |schedule: &[Gene]| -> HashSet<usize> {
+            model
+                .conflicts(&problem, schedule)
+                .into_iter()
+                .filter(|conflict| conflict.hardness == Hardness::Hard)
+                .flat_map(|conflict| conflict.classes)
+                .collect()
+        };
+
+        let mut decided = 0;
+        for _ in 0..50 {
+            let parent1 = generate_random_schedule(&candidates, &mut rng);
+            let parent2 = generate_random_schedule(&candidates, &mut rng);
+            let (conflicted1, conflicted2) = (hard_conflicts(&parent1), hard_conflicts(&parent2));
+
+            let child = resource_aware(
+                &parent1,
+                &model.hard_conflicted(&problem, &parent1),
+                &parent2,
+                &model.hard_conflicted(&problem, &parent2),
+                &mut rng,
+            );
+            for i in 0..child.len() {
+                assert!(child[i] == parent1[i] || child[i] == parent2[i]);
+                match (conflicted1.contains(&i), conflicted2.contains(&i)) {
+                    (false, true) => assert_eq!(child[i], parent1[i]),
+                    (true, false) => assert_eq!(child[i], parent2[i]),
+                    _ => continue,
+                }
+                decided += 1;
+            }
+        }
+        assert!(decided > 0, "no gene was conflict-free in just one parent");
+    }
+}
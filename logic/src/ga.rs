@@ -1 +1,340 @@
+use std::fmt;
+use std::time::{Duration as StdDuration, Instant};
 
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::candidates::Candidates;
+use crate::chromosome::{decode, Gene};
+use crate::config::{ConfigError, GaConfig};
+use crate::conflict::Conflict;
+use crate::constraint::{Fitness, FitnessModel};
+use crate::island::{evolve_all, migrate, Island};
+use crate::problem::{Problem, ProblemError};
+use crate::schedule::ClassSchedule;
+use crate::stats::GenerationStats;
+
+/// Why a solver run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// A schedule without any hard or soft violations was found.
+    Perfect,
+    /// A schedule without hard violations was found and
+    /// [`GaConfig::stop_when_feasible`] is set.
+    Feasible,
+    GenerationLimit,
+    /// [`GaConfig::stagnation_limit`] generations passed without improvement.
+    Stagnation,
+    TimeLimit,
+}
+
+/// The best schedule found by a solver run.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub schedule: Vec<ClassSchedule>,
+    pub fitness: Fitness,
+    pub conflicts: Vec<Conflict>,
+    /// Generation the schedule was first found in; 0 is the initial population.
+    pub generation: usize,
+    /// Number of generations evolved before the run stopped.
+    pub generations: usize,
+    pub stop_reason: StopReason,
+    /// Seed the run was started from; feeding it back through
+    /// [`GaConfig::seed`] reproduces the same schedule.
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverError {
+    Config(ConfigError),
+    Problem(ProblemError),
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::Config(err) => write!(f, "invalid config: {}", err),
+            SolverError::Problem(err) => write!(f, "invalid problem: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+impl From<ConfigError> for SolverError {
+    fn from(err: ConfigError) -> Self {
+        SolverError::Config(err)
+    }
+}
+
+impl From<ProblemError> for SolverError {
+    fn from(err: ProblemError) -> Self {
+        SolverError::Problem(err)
+    }
+}
+
+/// A schedule giving every course a random qualified instructor and suitable
+/// room, at a time the instructor prefers where possible.
+pub fn generate_random_schedule<R: Rng + ?Sized>(candidates: &Candidates, rng: &mut R) -> Vec<Gene> {
+    (0..candidates.len()).map(|course| candidates.favoured_gene(course, rng)).collect()
+}
+
+/// Picks one random class and a new qualified instructor, suitable room and
+/// time for it, keeping the meeting length and number of weekly meetings its
+/// course requires. The move is not applied, so it can be scored incrementally.
+pub fn random_move<R: Rng + ?Sized>(schedule: &[Gene], candidates: &Candidates, rng: &mut R) -> (usize, Gene) {
+    let index = rng.random_range(0..schedule.len());
+    (index, candidates.random_gene(index, rng))
+}
+
+/// Runs the genetic algorithm described by `config`, scoring schedules with
+/// `model`, and returns the best schedule seen in any generation.
+pub fn genetic_algorithm(problem: &Problem, model: &FitnessModel, config: &GaConfig) -> Result<Solution, SolverError> {
+    run(problem, model, config, None)
+}
+
+/// Like [`genetic_algorithm`], calling `on_generation` with statistics about
+/// every generation, including the initial population.
+pub fn genetic_algorithm_with_progress(
+    problem: &Problem,
+    model: &FitnessModel,
+    config: &GaConfig,
+    mut on_generation: impl FnMut(&GenerationStats),
+) -> Result<Solution, SolverError> {
+    run(problem, model, config, Some(&mut on_generation))
+}
+
+/// The solver loop; statistics are only gathered when someone listens.
+fn run(
+    problem: &Problem,
+    model: &FitnessModel,
+    config: &GaConfig,
+    mut on_generation: Option<&mut dyn FnMut(&GenerationStats)>,
+) -> Result<Solution, SolverError> {
+    config.validate()?;
+    problem.validate()?;
+    let candidates = Candidates::new(problem);
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+
+    let selection = config.selection.build(config.tournament_size);
+    let threads = config.worker_threads();
+    let started = Instant::now();
+    let deadline = config.time_limit_ms.map(|ms| started + StdDuration::from_millis(ms));
+
+    // Island `i` draws from `seed + i`, so a single island replays exactly
+    // like the plain algorithm; migration gets a stream of its own.
+    let mut islands: Vec<Island> = (0..config.islands as u64)
+        .map(|i| Island::new(problem, model, &candidates, config, seed.wrapping_add(i), threads, deadline))
+        .collect();
+    let mut migration_rng = StdRng::seed_from_u64(seed.wrapping_add(config.islands as u64));
+
+    let mut best: Option<(Vec<Gene>, Fitness, usize)> = None;
+    let mut generation = 0;
+
+    let stop_reason = loop {
+        let populations: Vec<(&[Vec<Gene>], &[Fitness])> = islands
+            .iter()
+            .map(|island| (island.population.as_slice(), island.fitness.as_slice()))
+            .collect();
+        if let Some(on_generation) = on_generation.as_mut() {
+            on_generation(&GenerationStats::collect(generation, &populations, started.elapsed()));
+        }
+
+        // Every island is sorted best-first; ties go to the lowest island.
+        let (leader, leader_fitness) = populations
+            .iter()
+            .map(|&(schedules, fitness)| (&schedules[0], &fitness[0]))
+            .min_by_key(|(_, fitness)| fitness.key())
+            .unwrap();
+        if best.as_ref().is_none_or(|(_, best_fitness, _)| leader_fitness.key() < best_fitness.key()) {
+            best = Some((leader.clone(), leader_fitness.clone(), generation));
+        }
+        let (_, best_fitness, found_in) = best.as_ref().unwrap();
+
+        if best_fitness.penalty() == 0 {
+            break StopReason::Perfect;
+        }
+        if config.stop_when_feasible && best_fitness.is_feasible() {
+            break StopReason::Feasible;
+        }
+        if generation == config.generations {
+            break StopReason::GenerationLimit;
+        }
+        if config.stagnation_limit.is_some_and(|limit| generation - found_in >= limit) {
+            break StopReason::Stagnation;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break StopReason::TimeLimit;
+        }
+
+        evolve_all(&mut islands, problem, model, &candidates, config, selection.as_ref(), threads);
+        generation += 1;
+        if islands.len() > 1 && generation % config.migration_interval == 0 {
+            migrate(&mut islands, config.topology, config.migrants, &mut migration_rng);
+        }
+    };
+
+    let generations = generation;
+    let (genes, fitness, generation) = best.unwrap();
+    let conflicts = model.conflicts(problem, &genes);
+    Ok(Solution {
+        schedule: decode(problem, &genes),
+        fitness,
+        conflicts,
+        generation,
+        generations,
+        stop_reason,
+        seed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromosome::encode;
+    use crate::testing;
+
+    #[test]
+    fn same_seed_gives_same_schedule() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let config = GaConfig {
+            seed: Some(7),
+            population_size: 12,
+            generations: 15,
+            islands: 3,
+            migration_interval: 4,
+            threads: 3,
+            ..GaConfig::default()
+        };
+
+        let first = genetic_algorithm(&problem, &model, &config).unwrap();
+        let second = genetic_algorithm(&problem, &model, &config).unwrap();
+        assert_eq!(first.seed, 7);
+        assert_eq!(first.schedule, second.schedule);
+        assert_eq!(first.fitness, second.fitness);
+        assert_eq!(first.generation, second.generation);
+    }
+
+    #[test]
+    fn more_generations_never_give_a_worse_schedule() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let mut previous: Option<Fitness> = None;
+
+        for generations in 0..12 {
+            let config = GaConfig {
+                seed: Some(5),
+                population_size: 10,
+                generations,
+                elite_count: 1,
+                ..GaConfig::default()
+            };
+            let solution = genetic_algorithm(&problem, &model, &config).unwrap();
+            assert_eq!(solution.fitness, model.evaluate(&problem, &encode(&problem, &solution.schedule).unwrap()));
+            assert!(solution.generation <= generations);
+            if let Some(previous) = previous {
+                assert!(solution.fitness.key() <= previous.key());
+            }
+            previous = Some(solution.fitness);
+        }
+    }
+
+    #[test]
+    fn elitism_keeps_the_best_penalty_from_rising() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let config = GaConfig {
+            seed: Some(13),
+            population_size: 10,
+            generations: 20,
+            elite_count: 1,
+            islands: 2,
+            migration_interval: 5,
+            ..GaConfig::default()
+        };
+
+        let mut best = Vec::new();
+        let solution = genetic_algorithm_with_progress(&problem, &model, &config, |stats| best.push(stats.best)).unwrap();
+        assert!(best.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert_eq!(Some(solution.fitness.penalty()), best.iter().copied().min());
+    }
+
+    #[test]
+    fn runs_stop_for_the_first_limit_reached() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let run = |config: GaConfig| {
+            genetic_algorithm(
+                &problem,
+                &model,
+                &GaConfig {
+                    seed: Some(9),
+                    population_size: 10,
+                    ..config
+                },
+            )
+            .unwrap()
+        };
+
+        let solution = run(GaConfig {
+            generations: 4,
+            ..GaConfig::default()
+        });
+        assert_eq!(solution.stop_reason, StopReason::GenerationLimit);
+        assert_eq!(solution.generations, 4);
+
+        let solution = run(GaConfig {
+            generations: 10_000,
+            stagnation_limit: Some(3),
+            ..GaConfig::default()
+        });
+        assert_eq!(solution.stop_reason, StopReason::Stagnation);
+        assert_eq!(solution.generations - solution.generation, 3);
+
+        let solution = run(GaConfig {
+            generations: 10_000,
+            time_limit_ms: Some(0),
+            ..GaConfig::default()
+        });
+        assert_eq!(solution.stop_reason, StopReason::TimeLimit);
+        assert_eq!(solution.generations, 0);
+    }
+
+    #[test]
+    fn mutation_keeps_meeting_lengths_and_patterns() {
+        let problem = testing::problem();
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut schedule = generate_random_schedule(&candidates, &mut rng);
+
+        for _ in 0..200 {
+            let (index, gene) = random_move(&schedule, &candidates, &mut rng);
+            schedule[index] = gene;
+            for (class, course) in decode(&problem, &schedule).iter().zip(&problem.courses) {
+                assert_eq!((class.end_time - class.start_time).num_minutes(), course.duration);
+                assert_eq!(Some(class.days.len()), course.meetings_per_week());
+            }
+        }
+    }
+
+    #[test]
+    fn thread_count_does_not_change_the_schedule() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        // Large enough for the islands to be spread over threads.
+        let config = |threads| GaConfig {
+            seed: Some(11),
+            population_size: 120,
+            generations: 8,
+            islands: 3,
+            migration_interval: 4,
+            threads,
+            ..GaConfig::default()
+        };
+
+        let sequential = genetic_algorithm(&problem, &model, &config(1)).unwrap();
+        let parallel = genetic_algorithm(&problem, &model, &config(4)).unwrap();
+        assert_eq!(sequential.schedule, parallel.schedule);
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{Duration, NaiveTime};
+
+use crate::schedule::{Days, TimeBlock};
+
+/// The teaching day cut into equal periods, e.g. five 90-minute periods from
+/// 08:00 with a break after the second and lunch blocked off.
+///
+/// Classes start at the beginning of a period and take up as many whole
+/// periods as their meetings need, so two classes overlap exactly when they
+/// share a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeGrid {
+    /// Days classes may meet on.
+    pub days: Days,
+    /// Start of the first period.
+    pub day_start: NaiveTime,
+    pub period_minutes: i64,
+    pub periods_per_day: usize,
+    /// Breaks `(after, minutes)` of `minutes` between period `after` and the
+    /// next. No class runs through a break.
+    pub breaks: Vec<(usize, i64)>,
+    /// Times no class may overlap, such as lunch.
+    pub blocks: Vec<TimeBlock>,
+}
+
+impl TimeGrid {
+    pub fn new(days: Days, day_start: NaiveTime, period_minutes: i64, periods_per_day: usize) -> Self {
+        Self {
+            days,
+            day_start,
+            period_minutes,
+            periods_per_day,
+            breaks: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn with_break(mut self, after: usize, minutes: i64) -> Self {
+        self.breaks.push((after, minutes));
+        self
+    }
+
+    pub fn with_blocks(mut self, blocks: &[TimeBlock]) -> Self {
+        self.blocks.extend_from_slice(blocks);
+        self
+    }
+
+    /// When period `period` starts, counting the breaks before it.
+    pub fn period_start(&self, period: usize) -> NaiveTime {
+        self.day_start + Duration::minutes(self.period_offset(period))
+    }
+
+    /// Minutes from the start of the day to the start of period `period`.
+    pub fn period_offset(&self, period: usize) -> i64 {
+        let breaks: i64 = self
+            .breaks
+            .iter()
+            .filter(|&&(after, _)| after < period)
+            .map(|&(_, minutes)| minutes)
+            .sum();
+        period as i64 * self.period_minutes + breaks
+    }
+
+    /// Start of every period of the day, in order.
+    pub fn start_times(&self) -> Vec<NaiveTime> {
+        (0..self.periods_per_day).map(|period| self.period_start(period)).collect()
+    }
+
+    /// Number of consecutive periods a meeting of `duration` minutes takes up.
+    pub fn periods_for(&self, duration: i64) -> usize {
+        ((duration + self.period_minutes - 1) / self.period_minutes).max(1) as usize
+    }
+
+    /// Whether a class meeting on `days` for `duration` minutes may start at
+    /// period `period`: it stays within the grid's days and periods, does not
+    /// run through a break and misses every block.
+    pub fn fits(&self, days: Days, period: usize, duration: i64) -> bool {
+        let last = period + self.periods_for(duration) - 1;
+        if days.intersection(self.days) != days || last >= self.periods_per_day {
+            return false;
+        }
+        if self.breaks.iter().any(|&(after, _)| period <= after && after < last) {
+            return false;
+        }
+
+        let start = self.period_start(period);
+        let end = start + Duration::minutes(duration);
+        !self
+            .blocks
+            .iter()
+            .any(|block| block.days.intersects(days) && block.start < end && start < block.end)
+    }
+
+    /// Periods a class meeting on `days` for `duration` minutes may start at.
+    pub fn valid_starts(&self, days: Days, duration: i64) -> Vec<usize> {
+        (0..self.periods_per_day)
+            .filter(|&period| self.fits(days, period, duration))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::time;
+
+    /// Five 90-minute periods from 08:00 on weekdays, with a half-hour break
+    /// after the second and lunch blocked off on MWF.
+    fn grid() -> TimeGrid {
+        TimeGrid::new("MTWThF".parse().unwrap(), time(8, 0), 90, 5)
+            .with_break(1, 30)
+            .with_blocks(&[TimeBlock::new(Days::MWF, time(13, 0), time(14, 0))])
+    }
+
+    #[test]
+    fn periods_start_after_breaks() {
+        assert_eq!(
+            grid().start_times(),
+            [time(8, 0), time(9, 30), time(11, 30), time(13, 0), time(14, 30)]
+        );
+        assert_eq!(grid().period_offset(2), 210);
+    }
+
+    #[test]
+    fn single_periods_avoid_blocked_days_only() {
+        assert_eq!(grid().valid_starts(Days::MWF, 60), [0, 1, 2, 4]);
+        assert_eq!(grid().valid_starts(Days::TTH, 60), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn longer_meetings_take_several_periods() {
+        let grid = grid();
+        assert_eq!(grid.periods_for(90), 1);
+        assert_eq!(grid.periods_for(150), 2);
+        // Period 1 runs through the break and 4 off the end of the day; on
+        // MWF, 2 and 3 also run into lunch.
+        assert_eq!(grid.valid_starts(Days::MWF, 150), [0]);
+        assert_eq!(grid.valid_starts(Days::TTH, 150), [0, 2, 3]);
+    }
+
+    #[test]
+    fn days_outside_the_grid_never_fit() {
+        let with_saturday: Days = "MS".parse().unwrap();
+        assert!(grid().valid_starts(with_saturday, 60).is_empty());
+        assert!(!grid().fits(Days::ALL, 0, 60));
+        assert!(grid().fits(Days::TTH, 0, 60));
+    }
+}
@@ -0,0 +1,301 @@
+use std::thread;
+use std::time::Instant;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::Deserialize;
+
+use crate::candidates::Candidates;
+use crate::chromosome::Gene;
+use crate::config::GaConfig;
+use crate::constraint::{useful_threads, Fitness, FitnessModel};
+use crate::crossover::{resource_aware, CrossoverOperator};
+use crate::ga::{generate_random_schedule, random_move};
+use crate::problem::Problem;
+use crate::repair::repair;
+use crate::selection::Selection;
+
+/// Which islands each island sends its best schedules to when migrating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topology {
+    /// Island `i` sends to island `i + 1`, the last one back to the first.
+    Ring,
+    /// Every island sends to every other island.
+    FullyConnected,
+    /// Every island sends to one other island, picked afresh each migration.
+    Random,
+}
+
+impl Topology {
+    /// The islands that island `from` of `islands` sends migrants to; none
+    /// when there is no other island.
+    pub fn targets<R: Rng + ?Sized>(self, from: usize, islands: usize, rng: &mut R) -> Vec<usize> {
+        if islands < 2 {
+            return Vec::new();
+        }
+        match self {
+            Topology::Ring => vec![(from + 1) % islands],
+            Topology::FullyConnected => (0..islands).filter(|&to| to != from).collect(),
+            Topology::Random => {
+                let to = rng.random_range(0..islands - 1);
+                vec![if to >= from { to + 1 } else { to }]
+            }
+        }
+    }
+}
+
+/// One independently evolving population, kept sorted best-first.
+pub(crate) struct Island {
+    rng: StdRng,
+    /// When the run's time is up; no more children are bred after it.
+    deadline: Option<Instant>,
+    pub population: Vec<Vec<Gene>>,
+    pub fitness: Vec<Fitness>,
+}
+
+impl Island {
+    /// A random population of `config.population_size` schedules.
+    pub fn new(
+        problem: &Problem,
+        model: &FitnessModel,
+        candidates: &Candidates,
+        config: &GaConfig,
+        seed: u64,
+        threads: usize,
+        deadline: Option<Instant>,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let initial: Vec<Vec<Gene>> = (0..config.population_size)
+            .map(|_| generate_random_schedule(candidates, &mut rng))
+            .collect();
+        let fitness = model.evaluate_all(problem, &initial, threads);
+        let (population, fitness) = rank(initial.into_iter().zip(fitness).collect());
+        Self {
+            rng,
+            deadline,
+            population,
+            fitness,
+        }
+    }
+
+    /// Replaces the population with the next generation. A generation cut
+    /// short by the deadline fills the places left with the best of the
+    /// current population.
+    pub fn evolve(
+        &mut self,
+        problem: &Problem,
+        model: &FitnessModel,
+        candidates: &Candidates,
+        config: &GaConfig,
+        selection: &dyn Selection,
+        threads: usize,
+    ) {
+        // Resource-aware crossover looks at which classes of each parent are
+        // conflicted; work that out once per schedule, not once per pairing.
+        let conflicted: Vec<Vec<usize>> = if config.crossover == CrossoverOperator::ResourceAware {
+            self.population
+                .iter()
+                .map(|schedule| model.hard_conflicted(problem, schedule))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let rng = &mut self.rng;
+        let mut children = Vec::with_capacity(config.population_size - config.elite_count);
+
+        while children.len() < config.population_size - config.elite_count {
+            if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            let first = selection.select(&self.fitness, rng);
+            // A copied parent brings its fitness along, so a mutation of it
+            // can be scored from the moved class alone.
+            let (mut child, mut child_fitness) = if rng.random_bool(config.crossover_rate) {
+                let second = selection.select(&self.fitness, rng);
+                let (parent1, parent2) = (&self.population[first], &self.population[second]);
+                let child = match config.crossover {
+                    CrossoverOperator::ResourceAware => {
+                        resource_aware(parent1, &conflicted[first], parent2, &conflicted[second], rng)
+                    }
+                    operator => operator.apply(problem, model, parent1, parent2, rng),
+                };
+                (child, None)
+            } else {
+                (self.population[first].clone(), Some(self.fitness[first].clone()))
+            };
+
+            if rng.random_bool(config.mutation_rate) {
+                let (index, gene) = random_move(&child, candidates, rng);
+                match &child_fitness {
+                    Some(known) => child_fitness = Some(model.evaluate_move(problem, &mut child, known, index, gene)),
+                    None => child[index] = gene,
+                }
+            }
+
+            if config.repair {
+                let known = child_fitness.unwrap_or_else(|| model.evaluate(problem, &child));
+                child_fitness = Some(repair(problem, model, candidates, &mut child, known, rng));
+            }
+
+            children.push((child, child_fitness));
+        }
+
+        // The elites, and the survivors of a generation cut short, go through
+        // unchanged and keep their fitness; only the offspring whose fitness
+        // isn't known yet need scoring.
+        let unscored: Vec<&[Gene]> = children
+            .iter()
+            .filter(|(_, fitness)| fitness.is_none())
+            .map(|(child, _)| child.as_slice())
+            .collect();
+        let mut fresh = model.evaluate_all(problem, &unscored, threads).into_iter();
+        let population = std::mem::take(&mut self.population);
+        let fitness = std::mem::take(&mut self.fitness);
+        (self.population, self.fitness) = rank(
+            population
+                .into_iter()
+                .zip(fitness)
+                .take(config.population_size - children.len())
+                .chain(
+                    children
+                        .into_iter()
+                        .map(|(child, fitness)| (child, fitness.unwrap_or_else(|| fresh.next().unwrap()))),
+                )
+                .collect(),
+        );
+    }
+
+    /// Copies of the `count` best schedules.
+    fn emigrants(&self, count: usize) -> Vec<(Vec<Gene>, Fitness)> {
+        self.population
+            .iter()
+            .cloned()
+            .zip(self.fitness.iter().cloned())
+            .take(count)
+            .collect()
+    }
+
+    /// Lets `migrants` in, dropping the worst schedules to keep the
+    /// population size.
+    fn immigrate(&mut self, migrants: Vec<(Vec<Gene>, Fitness)>) {
+        let size = self.population.len();
+        let population = std::mem::take(&mut self.population);
+        let fitness = std::mem::take(&mut self.fitness);
+        let mut scored: Vec<_> = population.into_iter().zip(fitness).chain(migrants).collect();
+        scored.sort_by_key(|(_, fitness)| fitness.key());
+        scored.truncate(size);
+        (self.population, self.fitness) = scored.into_iter().unzip();
+    }
+}
+
+/// Sorts scored schedules best-first, returning the schedules and their
+/// fitness side by side.
+fn rank(mut scored: Vec<(Vec<Gene>, Fitness)>) -> (Vec<Vec<Gene>>, Vec<Fitness>) {
+    scored.sort_by_key(|(_, fitness)| fitness.key());
+    scored.into_iter().unzip()
+}
+
+/// Evolves every island by one generation. A lone island scores its children
+/// on `threads` threads; several islands are instead spread over the threads
+/// and score their own children sequentially. Small populations stay on the
+/// calling thread either way.
+pub(crate) fn evolve_all(
+    islands: &mut [Island],
+    problem: &Problem,
+    model: &FitnessModel,
+    candidates: &Candidates,
+    config: &GaConfig,
+    selection: &dyn Selection,
+    threads: usize,
+) {
+    if let [island] = islands {
+        island.evolve(problem, model, candidates, config, selection, threads);
+        return;
+    }
+    let genes = islands.len() * config.population_size * problem.courses.len();
+    let threads = useful_threads(threads, genes);
+    if threads <= 1 {
+        for island in islands {
+            island.evolve(problem, model, candidates, config, selection, 1);
+        }
+        return;
+    }
+
+    let chunk_size = islands.len().div_ceil(threads);
+    thread::scope(|scope| {
+        for chunk in islands.chunks_mut(chunk_size) {
+            scope.spawn(move || {
+                for island in chunk {
+                    island.evolve(problem, model, candidates, config, selection, 1);
+                }
+            });
+        }
+    });
+}
+
+/// Sends copies of each island's `migrants` best schedules to its targets
+/// under `topology`. Every island picks its emigrants before any arrive.
+pub(crate) fn migrate<R: Rng + ?Sized>(islands: &mut [Island], topology: Topology, migrants: usize, rng: &mut R) {
+    let count = islands.len();
+    let mut arrivals: Vec<Vec<(Vec<Gene>, Fitness)>> = vec![Vec::new(); count];
+    for (from, island) in islands.iter().enumerate() {
+        let emigrants = island.emigrants(migrants);
+        for to in topology.targets(from, count, rng) {
+            arrivals[to].extend(emigrants.iter().cloned());
+        }
+    }
+    for (island, migrants) in islands.iter_mut().zip(arrivals) {
+        island.immigrate(migrants);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const TOPOLOGIES: [Topology; 3] = [Topology::Ring, Topology::FullyConnected, Topology::Random];
+
+    #[test]
+    fn a_lone_island_has_no_targets() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for topology in TOPOLOGIES {
+            assert!(topology.targets(0, 1, &mut rng).is_empty());
+            assert!(topology.targets(0, 0, &mut rng).is_empty());
+        }
+    }
+
+    #[test]
+    fn islands_never_send_to_themselves() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for topology in TOPOLOGIES {
+            for from in 0..4 {
+                let targets = topology.targets(from, 4, &mut rng);
+                assert!(!targets.is_empty());
+                assert!(targets.iter().all(|&to| to != from && to < 4));
+            }
+        }
+        assert_eq!(Topology::Ring.targets(3, 4, &mut rng), [0]);
+        assert_eq!(Topology::FullyConnected.targets(1, 4, &mut rng), [0, 2, 3]);
+    }
+
+    #[test]
+    fn no_children_are_bred_past_the_deadline() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let candidates = Candidates::new(&problem);
+        let config = GaConfig {
+            population_size: 10,
+            ..GaConfig::default()
+        };
+        let selection = config.selection.build(config.tournament_size);
+        let mut island = Island::new(&problem, &model, &candidates, &config, 3, 1, Some(Instant::now()));
+
+        let (population, fitness) = (island.population.clone(), island.fitness.clone());
+        island.evolve(&problem, &model, &candidates, &config, selection.as_ref(), 1);
+        assert_eq!(island.population, population);
+        assert_eq!(island.fitness, fitness);
+    }
+}
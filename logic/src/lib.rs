@@ -0,0 +1,31 @@
+pub mod candidates;
+pub mod chromosome;
+pub mod config;
+pub mod conflict;
+pub mod constraint;
+pub mod crossover;
+pub mod ga;
+pub mod grid;
+pub mod island;
+pub mod occupancy;
+pub mod problem;
+pub mod repair;
+pub mod schedule;
+pub mod selection;
+pub mod stats;
+#[cfg(test)]
+mod testing;
+
+pub use candidates::Candidates;
+pub use chromosome::{EncodeError, Gene};
+pub use config::{ConfigError, GaConfig};
+pub use conflict::{Conflict, ConflictKind, TimeWindow};
+pub use constraint::{Constraint, Fitness, FitnessModel, Hardness};
+pub use crossover::CrossoverOperator;
+pub use ga::{genetic_algorithm, genetic_algorithm_with_progress, Solution, SolverError, StopReason};
+pub use grid::TimeGrid;
+pub use island::Topology;
+pub use problem::{Course, Instructor, LoadLimits, Problem, ProblemError, Room, Student};
+pub use schedule::{ClassSchedule, Days, TimeBlock};
+pub use selection::{Selection, SelectionStrategy};
+pub use stats::GenerationStats;
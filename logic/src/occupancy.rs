@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use chrono::Weekday;
+
+use crate::conflict::TimeWindow;
+
+/// The classes holding each resource (instructor, room, group, ...) on each
+/// day, sorted by start time, so that clashes are found by sweeping every
+/// bucket once instead of comparing every pair of classes.
+pub struct Occupancy<K> {
+    buckets: HashMap<(K, Weekday), Vec<usize>>,
+}
+
+impl<K: Hash + Eq + Copy> Occupancy<K> {
+    /// Indexes every class, given by when it meets, under each of the
+    /// resources `resources` returns for it.
+    pub fn new<I>(windows: &[TimeWindow], resources: impl Fn(usize) -> I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut buckets: HashMap<(K, Weekday), Vec<usize>> = HashMap::new();
+        for (i, window) in windows.iter().enumerate() {
+            for resource in resources(i) {
+                for day in window.days.iter() {
+                    buckets.entry((resource, day)).or_default().push(i);
+                }
+            }
+        }
+        for bucket in buckets.values_mut() {
+            bucket.sort_by_key(|&i| windows[i].start);
+        }
+        Self { buckets }
+    }
+
+    /// Pairs `(i, j)`, `i < j`, of classes holding a common resource at
+    /// overlapping times. Each pair is reported once, in ascending order.
+    pub fn clashes(&self, windows: &[TimeWindow]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+
+        for bucket in self.buckets.values() {
+            active.clear();
+            for &i in bucket {
+                // Everything still active started no later than `i`, so it
+                // overlaps `i` exactly when it ends after `i` starts.
+                active.retain(|&j| windows[j].end > windows[i].start);
+                pairs.extend(active.iter().map(|&j| (j.min(i), j.max(i))));
+                active.push(i);
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Takes class `index`, which met at `window`, off `resource`.
+    pub fn remove(&mut self, resource: K, window: &TimeWindow, index: usize) {
+        for day in window.days.iter() {
+            if let Some(bucket) = self.buckets.get_mut(&(resource, day)) {
+                bucket.retain(|&i| i != index);
+            }
+        }
+    }
+
+    /// Puts class `index`, meeting at `windows[index]`, on `resource`, so a
+    /// moved class need not rebuild the whole index.
+    pub fn insert(&mut self, windows: &[TimeWindow], resource: K, index: usize) {
+        let window = &windows[index];
+        for day in window.days.iter() {
+            let bucket = self.buckets.entry((resource, day)).or_default();
+            let at = bucket.partition_point(|&i| windows[i].start <= window.start);
+            bucket.insert(at, index);
+        }
+    }
+
+    /// Whether no class other than `except` holds `resource` at any time
+    /// `window` covers.
+    pub fn is_free(&self, windows: &[TimeWindow], resource: K, window: &TimeWindow, except: usize) -> bool {
+        window.days.iter().all(|day| {
+            self.buckets.get(&(resource, day)).is_none_or(|bucket| {
+                bucket
+                    .iter()
+                    .all(|&j| j == except || windows[j].end <= window.start || windows[j].start >= window.end)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Days;
+    use crate::testing::time;
+
+    fn window(days: Days, hour: u32) -> TimeWindow {
+        TimeWindow {
+            days,
+            start: time(hour, 0),
+            end: time(hour + 1, 0),
+        }
+    }
+
+    #[test]
+    fn moving_a_class_matches_rebuilding() {
+        let rooms = [0, 0, 1, 0];
+        let mut windows = vec![
+            window(Days::MWF, 8),
+            window(Days::MWF, 9),
+            window(Days::TTH, 8),
+            window(Days::TTH, 10),
+        ];
+        let mut occupancy = Occupancy::new(&windows, |i| [rooms[i]]);
+        assert!(occupancy.clashes(&windows).is_empty());
+
+        // Class 3 moves onto class 0's room and time.
+        occupancy.remove(rooms[3], &windows[3], 3);
+        windows[3] = window(Days::MWF, 8);
+        occupancy.insert(&windows, rooms[3], 3);
+
+        let rebuilt = Occupancy::new(&windows, |i| [rooms[i]]);
+        assert_eq!(occupancy.clashes(&windows), [(0, 3)]);
+        assert_eq!(occupancy.clashes(&windows), rebuilt.clashes(&windows));
+        assert!(!occupancy.is_free(&windows, 0, &window(Days::MWF, 8), 1));
+        assert!(occupancy.is_free(&windows, 0, &window(Days::TTH, 10), 1));
+    }
+}
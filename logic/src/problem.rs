@@ -0,0 +1,594 @@
+use std::fmt;
+
+use chrono::{NaiveTime, Timelike};
+
+use crate::grid::TimeGrid;
+use crate::schedule::{Days, TimeBlock};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Course {
+    pub name: String,
+    /// Length of a single meeting in minutes.
+    pub duration: i64,
+    /// Contact minutes per week, spread evenly over the meeting days.
+    pub weekly_minutes: i64,
+    /// Number of students expected to attend.
+    pub enrollment: u32,
+    /// Features, e.g. `lab` or `computers`, the room must provide.
+    pub required_features: Vec<String>,
+    /// Student groups (sections, cohorts, curricula) that take this course
+    /// together and so cannot have it overlap their other courses.
+    pub groups: Vec<String>,
+}
+
+impl Course {
+    pub fn new(name: &str, duration: i64, weekly_minutes: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            duration,
+            weekly_minutes,
+            enrollment: 0,
+            required_features: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn with_enrollment(mut self, enrollment: u32) -> Self {
+        self.enrollment = enrollment;
+        self
+    }
+
+    pub fn requires(mut self, features: &[&str]) -> Self {
+        self.required_features = features.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    pub fn for_groups(mut self, groups: &[&str]) -> Self {
+        self.groups = groups.iter().map(|g| g.to_string()).collect();
+        self
+    }
+
+    /// Student groups taking both this course and `other`.
+    pub fn shared_groups<'a>(&'a self, other: &'a Course) -> impl Iterator<Item = &'a String> {
+        self.groups.iter().filter(|group| other.groups.contains(group))
+    }
+
+    /// How many days a week the course has to meet, if its weekly contact
+    /// time is a whole number of meetings.
+    pub fn meetings_per_week(&self) -> Option<usize> {
+        if self.duration <= 0 || self.weekly_minutes <= 0 || self.weekly_minutes % self.duration != 0 {
+            return None;
+        }
+        Some((self.weekly_minutes / self.duration) as usize)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    pub name: String,
+    pub capacity: u32,
+    pub features: Vec<String>,
+}
+
+impl Room {
+    pub fn new(name: &str, capacity: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            capacity,
+            features: Vec::new(),
+        }
+    }
+
+    pub fn with_features(mut self, features: &[&str]) -> Self {
+        self.features = features.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    pub fn fits(&self, course: &Course) -> bool {
+        course.enrollment <= self.capacity
+    }
+
+    pub fn provides(&self, course: &Course) -> bool {
+        course.required_features.iter().all(|feature| self.features.contains(feature))
+    }
+}
+
+/// Upper bounds on how much an instructor may teach; `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadLimits {
+    pub max_daily_minutes: Option<i64>,
+    pub max_weekly_minutes: Option<i64>,
+    /// Longest run of back-to-back classes on one day.
+    pub max_consecutive: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instructor {
+    pub name: String,
+    /// Names of the courses this instructor is qualified to teach.
+    pub courses: Vec<String>,
+    /// When the instructor can teach at all; empty means any time.
+    pub availability: Vec<TimeBlock>,
+    /// When the instructor would rather teach; empty means no preference.
+    pub preferred: Vec<TimeBlock>,
+    pub limits: LoadLimits,
+}
+
+impl Instructor {
+    pub fn new(name: &str, courses: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            courses: courses.iter().map(|c| c.to_string()).collect(),
+            availability: Vec::new(),
+            preferred: Vec::new(),
+            limits: LoadLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: LoadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_availability(mut self, blocks: &[TimeBlock]) -> Self {
+        self.availability = blocks.to_vec();
+        self
+    }
+
+    pub fn with_preferences(mut self, blocks: &[TimeBlock]) -> Self {
+        self.preferred = blocks.to_vec();
+        self
+    }
+
+    pub fn is_available(&self, days: Days, start: NaiveTime, end: NaiveTime) -> bool {
+        within(&self.availability, days, start, end)
+    }
+
+    pub fn prefers(&self, days: Days, start: NaiveTime, end: NaiveTime) -> bool {
+        within(&self.preferred, days, start, end)
+    }
+
+    pub fn can_teach(&self, course: &Course) -> bool {
+        self.courses.contains(&course.name)
+    }
+}
+
+/// An individual student and the courses they are enrolled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Student {
+    pub id: String,
+    pub courses: Vec<String>,
+}
+
+impl Student {
+    pub fn new(id: &str, courses: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            courses: courses.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+/// Whether every meeting of a class falls inside one of `blocks`. An empty
+/// list places no restriction.
+fn within(blocks: &[TimeBlock], days: Days, start: NaiveTime, end: NaiveTime) -> bool {
+    blocks.is_empty()
+        || days
+            .iter()
+            .all(|day| blocks.iter().any(|block| block.contains(day, start, end)))
+}
+
+/// Everything the solver needs to know about a term: what has to be
+/// scheduled and the resources it can be scheduled into.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub courses: Vec<Course>,
+    pub instructors: Vec<Instructor>,
+    pub rooms: Vec<Room>,
+    /// Weekly meeting patterns a class may be given, e.g. MWF or TTh.
+    pub patterns: Vec<Days>,
+    /// Start times a class may be given on each of its meeting days.
+    pub times: Vec<NaiveTime>,
+    /// Individual enrollments, used to keep each student's courses apart.
+    pub students: Vec<Student>,
+    /// The period grid `times` was taken from, if any, which further limits
+    /// where each course may start.
+    pub grid: Option<TimeGrid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemError {
+    NoCourses,
+    NoTimes,
+    UnevenContactTime { course: String },
+    NoMatchingPattern { course: String, meetings: usize },
+    NoQualifiedInstructor { course: String },
+    /// Courses that no room is both large enough and equipped for.
+    NoSuitableRoom { courses: Vec<String> },
+    UnknownCourse { student: String, course: String },
+    /// No start period on the grid leaves room for a meeting of the course.
+    NoValidStart { course: String },
+    /// A meeting of the course starting at `start` would not end before
+    /// midnight: on a grid, at one of its periods; otherwise even at the
+    /// earliest start time.
+    PastMidnight { course: String, start: NaiveTime },
+    InvalidPeriodLength { minutes: i64 },
+    /// `times` are not the start times of the grid's periods, in order.
+    TimesOffGrid,
+    /// More instructors, rooms or slots than a [`Gene`](crate::chromosome::Gene) can index.
+    TooManyEntries { table: &'static str, count: usize },
+}
+
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemError::NoCourses => write!(f, "there are no courses to schedule"),
+            ProblemError::NoTimes => write!(f, "there are no start times"),
+            ProblemError::UnevenContactTime { course } => write!(
+                f,
+                "weekly contact time of {} is not a whole number of meetings",
+                course
+            ),
+            ProblemError::NoMatchingPattern { course, meetings } => write!(
+                f,
+                "no meeting pattern has the {} days a week {} needs",
+                meetings, course
+            ),
+            ProblemError::NoQualifiedInstructor { course } => {
+                write!(f, "no instructor is qualified to teach {}", course)
+            }
+            ProblemError::NoSuitableRoom { courses } => write!(
+                f,
+                "no room is large enough and equipped for {}",
+                courses.join(", ")
+            ),
+            ProblemError::UnknownCourse { student, course } => {
+                write!(f, "student {} is enrolled in unknown course {}", student, course)
+            }
+            ProblemError::NoValidStart { course } => write!(
+                f,
+                "no period on the grid leaves room for a meeting of {}",
+                course
+            ),
+            ProblemError::PastMidnight { course, start } => write!(
+                f,
+                "a meeting of {} starting at {} would run past midnight",
+                course,
+                start.format("%H:%M")
+            ),
+            ProblemError::InvalidPeriodLength { minutes } => {
+                write!(f, "periods must be at least a minute long, not {}", minutes)
+            }
+            ProblemError::TimesOffGrid => write!(f, "start times do not match the periods of the grid"),
+            ProblemError::TooManyEntries { table, count } => write!(
+                f,
+                "{} {} are more than a schedule can refer to (at most {})",
+                count,
+                table,
+                MAX_ENTRIES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProblemError {}
+
+/// Most instructors, rooms or slots a problem may have, since genes store
+/// their indices as `u16`.
+pub const MAX_ENTRIES: usize = u16::MAX as usize + 1;
+
+impl Problem {
+    pub fn new(
+        courses: Vec<Course>,
+        instructors: Vec<Instructor>,
+        rooms: Vec<Room>,
+        patterns: &[Days],
+        times: &[NaiveTime],
+    ) -> Self {
+        Self {
+            courses,
+            instructors,
+            rooms,
+            patterns: patterns.to_vec(),
+            times: times.to_vec(),
+            students: Vec::new(),
+            grid: None,
+        }
+    }
+
+    /// A problem whose classes start on the periods of `grid`, only where a
+    /// meeting of their course fits.
+    pub fn on_grid(
+        courses: Vec<Course>,
+        instructors: Vec<Instructor>,
+        rooms: Vec<Room>,
+        patterns: &[Days],
+        grid: TimeGrid,
+    ) -> Self {
+        Self {
+            times: grid.start_times(),
+            grid: Some(grid),
+            ..Self::new(courses, instructors, rooms, patterns, &[])
+        }
+    }
+
+    pub fn with_students(mut self, students: Vec<Student>) -> Self {
+        self.students = students;
+        self
+    }
+
+    pub fn course_index(&self, name: &str) -> Option<usize> {
+        self.courses.iter().position(|course| course.name == name)
+    }
+
+    /// Meeting patterns with as many days as `course` meets per week.
+    pub fn patterns_for(&self, course: &Course) -> Vec<Days> {
+        self.patterns
+            .iter()
+            .copied()
+            .filter(|days| Some(days.len()) == course.meetings_per_week())
+            .collect()
+    }
+
+    /// Number of slots, every meeting pattern paired with every start time.
+    pub fn slot_count(&self) -> usize {
+        self.patterns.len() * self.times.len()
+    }
+
+    /// The meeting pattern and start time of slot `slot`.
+    pub fn slot(&self, slot: usize) -> (Days, NaiveTime) {
+        (self.patterns[slot / self.times.len()], self.times[slot % self.times.len()])
+    }
+
+    pub fn slot_index(&self, days: Days, start: NaiveTime) -> Option<usize> {
+        let pattern = self.patterns.iter().position(|&p| p == days)?;
+        let time = self.times.iter().position(|&t| t == start)?;
+        Some(pattern * self.times.len() + time)
+    }
+
+    /// Slots whose meeting pattern suits `course`, see [`Problem::patterns_for`],
+    /// and at which a meeting of `course` fits: on a grid, within its periods,
+    /// otherwise before midnight.
+    pub fn slots_for(&self, course: &Course) -> Vec<usize> {
+        (0..self.slot_count())
+            .filter(|&slot| {
+                let days = self.slot(slot).0;
+                let period = slot % self.times.len();
+                Some(days.len()) == course.meetings_per_week()
+                    && match &self.grid {
+                        Some(grid) => grid.fits(days, period, course.duration),
+                        None => self.ends_before_midnight(slot, course.duration),
+                    }
+            })
+            .collect()
+    }
+
+    /// Whether a meeting of `duration` minutes in slot `slot` ends on the day
+    /// it starts. Times wrap at midnight, so one that doesn't would seem to
+    /// end before it starts.
+    fn ends_before_midnight(&self, slot: usize, duration: i64) -> bool {
+        let start = match &self.grid {
+            Some(grid) => {
+                i64::from(grid.day_start.num_seconds_from_midnight()) + 60 * grid.period_offset(slot % self.times.len())
+            }
+            None => i64::from(self.slot(slot).1.num_seconds_from_midnight()),
+        };
+        start + 60 * duration < 24 * 60 * 60
+    }
+
+    pub fn qualified_instructors(&self, course: &Course) -> Vec<&Instructor> {
+        self.qualified_instructor_indices(course)
+            .into_iter()
+            .map(|i| &self.instructors[i])
+            .collect()
+    }
+
+    pub fn qualified_instructor_indices(&self, course: &Course) -> Vec<usize> {
+        (0..self.instructors.len())
+            .filter(|&i| self.instructors[i].can_teach(course))
+            .collect()
+    }
+
+    pub fn instructor(&self, name: &str) -> Option<&Instructor> {
+        self.instructors.iter().find(|instructor| instructor.name == name)
+    }
+
+    pub fn instructor_index(&self, name: &str) -> Option<usize> {
+        self.instructors.iter().position(|instructor| instructor.name == name)
+    }
+
+    /// Rooms large enough to hold `course` that have every feature it needs.
+    pub fn suitable_rooms(&self, course: &Course) -> Vec<&Room> {
+        self.suitable_room_indices(course)
+            .into_iter()
+            .map(|i| &self.rooms[i])
+            .collect()
+    }
+
+    pub fn suitable_room_indices(&self, course: &Course) -> Vec<usize> {
+        (0..self.rooms.len())
+            .filter(|&i| self.rooms[i].fits(course) && self.rooms[i].provides(course))
+            .collect()
+    }
+
+    pub fn room(&self, name: &str) -> Option<&Room> {
+        self.rooms.iter().find(|room| room.name == name)
+    }
+
+    pub fn room_index(&self, name: &str) -> Option<usize> {
+        self.rooms.iter().position(|room| room.name == name)
+    }
+
+    /// Checks that every course can be given at least one placement.
+    pub fn validate(&self) -> Result<(), ProblemError> {
+        if self.courses.is_empty() {
+            return Err(ProblemError::NoCourses);
+        }
+        if let Some(grid) = &self.grid {
+            if grid.period_minutes < 1 {
+                return Err(ProblemError::InvalidPeriodLength { minutes: grid.period_minutes });
+            }
+            // Slots count periods by position in `times`.
+            if self.times != grid.start_times() {
+                return Err(ProblemError::TimesOffGrid);
+            }
+        }
+        if self.times.is_empty() {
+            return Err(ProblemError::NoTimes);
+        }
+        for (table, count) in [
+            ("instructors", self.instructors.len()),
+            ("rooms", self.rooms.len()),
+            ("slots", self.slot_count()),
+        ] {
+            if count > MAX_ENTRIES {
+                return Err(ProblemError::TooManyEntries { table, count });
+            }
+        }
+        for course in &self.courses {
+            let meetings = course
+                .meetings_per_week()
+                .ok_or_else(|| ProblemError::UnevenContactTime { course: course.name.clone() })?;
+            if self.patterns_for(course).is_empty() {
+                return Err(ProblemError::NoMatchingPattern {
+                    course: course.name.clone(),
+                    meetings,
+                });
+            }
+            let slots = self.slots_for(course);
+            if slots.is_empty() {
+                // Without a grid, starts are only left out for running past
+                // midnight.
+                return Err(match self.times.iter().min() {
+                    Some(&start) if self.grid.is_none() => ProblemError::PastMidnight {
+                        course: course.name.clone(),
+                        start,
+                    },
+                    _ => ProblemError::NoValidStart { course: course.name.clone() },
+                });
+            }
+            if let Some(&slot) = slots.iter().find(|&&slot| !self.ends_before_midnight(slot, course.duration)) {
+                return Err(ProblemError::PastMidnight {
+                    course: course.name.clone(),
+                    start: self.slot(slot).1,
+                });
+            }
+            if self.qualified_instructors(course).is_empty() {
+                return Err(ProblemError::NoQualifiedInstructor { course: course.name.clone() });
+            }
+        }
+
+        let unplaceable: Vec<String> = self
+            .courses
+            .iter()
+            .filter(|course| self.suitable_rooms(course).is_empty())
+            .map(|course| course.name.clone())
+            .collect();
+        if !unplaceable.is_empty() {
+            return Err(ProblemError::NoSuitableRoom { courses: unplaceable });
+        }
+
+        for student in &self.students {
+            if let Some(course) = student.courses.iter().find(|c| self.course_index(c).is_none()) {
+                return Err(ProblemError::UnknownCourse {
+                    student: student.id.clone(),
+                    course: course.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::time;
+
+    fn problem(courses: Vec<Course>) -> Problem {
+        let times = [time(8, 0)];
+        let names: Vec<&str> = courses.iter().map(|course| course.name.as_str()).collect();
+        let instructors = vec![Instructor::new("Alice", &names)];
+        Problem::new(courses, instructors, vec![Room::new("Room 101", 30)], &[Days::MWF, Days::TTH], &times)
+    }
+
+    #[test]
+    fn meetings_follow_from_weekly_contact_time() {
+        assert_eq!(Course::new("Math", 60, 180).meetings_per_week(), Some(3));
+        assert_eq!(Course::new("English", 90, 180).meetings_per_week(), Some(2));
+        assert_eq!(Course::new("Art", 90, 200).meetings_per_week(), None);
+        assert_eq!(Course::new("Nothing", 0, 0).meetings_per_week(), None);
+    }
+
+    #[test]
+    fn rejects_courses_no_pattern_fits() {
+        assert_eq!(problem(vec![Course::new("Math", 60, 180)]).validate(), Ok(()));
+        assert_eq!(
+            problem(vec![Course::new("Art", 90, 200)]).validate(),
+            Err(ProblemError::UnevenContactTime { course: "Art".to_string() })
+        );
+        assert_eq!(
+            problem(vec![Course::new("Lab", 60, 240)]).validate(),
+            Err(ProblemError::NoMatchingPattern {
+                course: "Lab".to_string(),
+                meetings: 4,
+            })
+        );
+    }
+
+    fn late_problem(times: &[NaiveTime]) -> Problem {
+        let courses = vec![Course::new("Night Class", 90, 270)];
+        let instructors = vec![Instructor::new("Owl", &["Night Class"])];
+        let rooms = vec![Room::new("Room 1", 30)];
+        Problem::new(courses, instructors, rooms, &[Days::MWF], times)
+    }
+
+    #[test]
+    fn leaves_out_starts_running_past_midnight() {
+        let problem = late_problem(&[time(20, 0), time(22, 0)]);
+        assert_eq!(problem.validate(), Ok(()));
+        assert_eq!(problem.slots_for(&problem.courses[0]), vec![0, 1]);
+
+        // Only the course's own late starts are left out.
+        let mut problem = late_problem(&[time(20, 0), time(23, 0)]);
+        problem.courses.push(Course::new("Short Class", 30, 90));
+        problem.instructors[0].courses.push("Short Class".to_string());
+        assert_eq!(problem.validate(), Ok(()));
+        assert_eq!(problem.slots_for(&problem.courses[0]), vec![0]);
+        assert_eq!(problem.slots_for(&problem.courses[1]), vec![0, 1]);
+    }
+
+    #[test]
+    fn rejects_courses_with_every_start_running_past_midnight() {
+        assert_eq!(
+            late_problem(&[time(23, 0), time(22, 45)]).validate(),
+            Err(ProblemError::PastMidnight {
+                course: "Night Class".to_string(),
+                start: time(22, 45),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_grid_periods_running_past_midnight() {
+        let courses = vec![Course::new("Night Class", 90, 270)];
+        let instructors = vec![Instructor::new("Owl", &["Night Class"])];
+        let rooms = vec![Room::new("Room 1", 30)];
+        let grid = TimeGrid::new(Days::MWF, time(20, 0), 90, 3);
+        let problem = Problem::on_grid(courses, instructors, rooms, &[Days::MWF], grid);
+        assert!(matches!(problem.validate(), Err(ProblemError::PastMidnight { .. })));
+    }
+
+    #[test]
+    fn rejects_times_that_differ_from_the_grid() {
+        let courses = vec![Course::new("Day Class", 90, 270)];
+        let instructors = vec![Instructor::new("Lark", &["Day Class"])];
+        let rooms = vec![Room::new("Room 1", 30)];
+        let grid = TimeGrid::new(Days::MWF, time(8, 0), 90, 4);
+        let mut problem = Problem::on_grid(courses, instructors, rooms, &[Days::MWF], grid);
+        assert_eq!(problem.validate(), Ok(()));
+
+        problem.times.pop();
+        assert_eq!(problem.validate(), Err(ProblemError::TimesOffGrid));
+    }
+}
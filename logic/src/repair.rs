@@ -0,0 +1,111 @@
+use rand::prelude::*;
+
+use crate::candidates::Candidates;
+use crate::chromosome::Gene;
+use crate::conflict::TimeWindow;
+use crate::constraint::{Fitness, FitnessModel};
+use crate::occupancy::Occupancy;
+use crate::problem::Problem;
+
+/// Placements tried per offending class before giving up on it.
+const REPAIR_ATTEMPTS: usize = 32;
+
+/// Offending classes a single repair looks at, so that repairing a badly
+/// broken child costs no more than a few dozen moves.
+const MAX_REPAIRED_CLASSES: usize = 8;
+
+/// Greedily moves up to [`MAX_REPAIRED_CLASSES`] random classes involved in
+/// hard conflicts, trying for each up to [`REPAIR_ATTEMPTS`] random placements
+/// with a qualified instructor, suitable room and time; those leaving both
+/// the instructor and the room free are tried first. A move is kept only
+/// when it lowers the hard penalty. Takes the schedule's current `fitness`
+/// and returns its fitness after repair.
+pub fn repair<R: Rng + ?Sized>(
+    problem: &Problem,
+    model: &FitnessModel,
+    candidates: &Candidates,
+    schedule: &mut [Gene],
+    mut fitness: Fitness,
+    rng: &mut R,
+) -> Fitness {
+    if fitness.is_feasible() {
+        return fitness;
+    }
+    let mut offenders = model.hard_conflicted(problem, schedule);
+    offenders.shuffle(rng);
+    offenders.truncate(MAX_REPAIRED_CLASSES);
+
+    let mut windows: Vec<TimeWindow> = schedule
+        .iter()
+        .enumerate()
+        .map(|(i, gene)| gene.window(problem, i))
+        .collect();
+    // Who holds what when; kept up to date as classes move.
+    let mut instructors = Occupancy::new(&windows, |i| [schedule[i].instructor()]);
+    let mut rooms = Occupancy::new(&windows, |i| [schedule[i].room()]);
+
+    for index in offenders {
+        if fitness.is_feasible() {
+            break;
+        }
+        let is_free = |gene: &Gene| {
+            let window = gene.window(problem, index);
+            instructors.is_free(&windows, gene.instructor(), &window, index)
+                && rooms.is_free(&windows, gene.room(), &window, index)
+        };
+        let mut placements: Vec<Gene> = (0..REPAIR_ATTEMPTS)
+            .map(|_| candidates.random_gene(index, rng))
+            .collect();
+        // Stable, so placements keep their random order within each half.
+        placements.sort_by_key(|gene| !is_free(gene));
+
+        // Only the hard penalty decides, so trial placements skip the soft
+        // constraints; the kept move is then scored in full.
+        let original = schedule[index];
+        let before = model.hard_class_penalty(problem, schedule, index);
+        let better = placements.into_iter().find(|&gene| {
+            schedule[index] = gene;
+            model.hard_class_penalty(problem, schedule, index) < before
+        });
+        schedule[index] = original;
+
+        if let Some(gene) = better {
+            fitness = model.evaluate_move(problem, schedule, &fitness, index, gene);
+            instructors.remove(original.instructor(), &windows[index], index);
+            rooms.remove(original.room(), &windows[index], index);
+            windows[index] = gene.window(problem, index);
+            instructors.insert(&windows, gene.instructor(), index);
+            rooms.insert(&windows, gene.room(), index);
+        }
+    }
+
+    fitness
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::ga::generate_random_schedule;
+    use crate::testing;
+
+    #[test]
+    fn repair_lowers_the_hard_penalty_and_reports_the_new_fitness() {
+        let problem = testing::problem();
+        let model = FitnessModel::for_problem(&problem);
+        let candidates = Candidates::new(&problem);
+        let mut rng = StdRng::seed_from_u64(21);
+
+        let mut improved = 0;
+        for _ in 0..50 {
+            let mut schedule = generate_random_schedule(&candidates, &mut rng);
+            let before = model.evaluate(&problem, &schedule);
+            let after = repair(&problem, &model, &candidates, &mut schedule, before.clone(), &mut rng);
+            assert_eq!(after, model.evaluate(&problem, &schedule));
+            assert!(after.hard <= before.hard);
+            improved += usize::from(after.hard < before.hard);
+        }
+        assert!(improved > 0);
+    }
+}
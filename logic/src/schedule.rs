@@ -0,0 +1,161 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveTime, Weekday};
+use serde::{Serialize, Serializer};
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// The weekdays a class meets on, e.g. MWF or TTh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Days(u8);
+
+impl Days {
+    pub const MWF: Days = Days(1 << 0 | 1 << 2 | 1 << 4);
+    pub const TTH: Days = Days(1 << 1 | 1 << 3);
+    pub const ALL: Days = Days(0x7f);
+
+    pub fn from_weekdays(days: &[Weekday]) -> Self {
+        Days(days.iter().fold(0, |bits, day| bits | Self::bit(*day)))
+    }
+
+    fn bit(day: Weekday) -> u8 {
+        1 << day.num_days_from_monday()
+    }
+
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & Self::bit(day) != 0
+    }
+
+    pub fn intersection(self, other: Days) -> Days {
+        Days(self.0 & other.0)
+    }
+
+    pub fn intersects(self, other: Days) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Weekday> {
+        WEEK.into_iter().filter(move |day| self.contains(*day))
+    }
+}
+
+impl fmt::Display for Days {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for day in self.iter() {
+            let abbreviation = match day {
+                Weekday::Mon => "M",
+                Weekday::Tue => "T",
+                Weekday::Wed => "W",
+                Weekday::Thu => "Th",
+                Weekday::Fri => "F",
+                Weekday::Sat => "S",
+                Weekday::Sun => "Su",
+            };
+            f.write_str(abbreviation)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDaysError(String);
+
+impl fmt::Display for ParseDaysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid day pattern {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDaysError {}
+
+impl FromStr for Days {
+    type Err = ParseDaysError;
+
+    /// Parses patterns such as `MWF`, `TTh` or `MTWThF`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = Vec::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            let day = match c {
+                'M' => Weekday::Mon,
+                'T' if chars.next_if_eq(&'h').is_some() => Weekday::Thu,
+                'T' => Weekday::Tue,
+                'W' => Weekday::Wed,
+                'F' => Weekday::Fri,
+                'S' if chars.next_if_eq(&'u').is_some() => Weekday::Sun,
+                'S' => Weekday::Sat,
+                _ => return Err(ParseDaysError(s.to_string())),
+            };
+            days.push(day);
+        }
+        if days.is_empty() {
+            return Err(ParseDaysError(s.to_string()));
+        }
+        Ok(Days::from_weekdays(&days))
+    }
+}
+
+impl Serialize for Days {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A recurring stretch of the week, e.g. MTWThF 08:00-12:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimeBlock {
+    pub days: Days,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeBlock {
+    pub fn new(days: Days, start: NaiveTime, end: NaiveTime) -> Self {
+        Self { days, start, end }
+    }
+
+    /// Whether a meeting from `start` to `end` on `day` lies inside the block.
+    pub fn contains(&self, day: Weekday, start: NaiveTime, end: NaiveTime) -> bool {
+        self.days.contains(day) && self.start <= start && end <= self.end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSchedule {
+    pub course: String,
+    pub instructor: String,
+    pub room: String,
+    pub days: Days,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+impl ClassSchedule {
+    pub fn new(course: &str, instructor: &str, room: &str, days: Days, start_time: NaiveTime, duration: i64) -> Self {
+        Self {
+            course: course.to_string(),
+            instructor: instructor.to_string(),
+            room: room.to_string(),
+            days,
+            start_time,
+            end_time: start_time + Duration::minutes(duration),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use rand::seq::index;
+use rand::{Rng, RngCore};
+use serde::Deserialize;
+
+use crate::constraint::Fitness;
+
+/// Picks parents for the next generation.
+pub trait Selection: Send + Sync {
+    /// Returns the index of the chosen parent. `ranked` holds the fitness of
+    /// every individual, sorted best-first.
+    fn select(&self, ranked: &[Fitness], rng: &mut dyn RngCore) -> usize;
+}
+
+/// Best of `size` distinct individuals drawn at random, or of everyone when
+/// there are no more than `size`.
+pub struct Tournament {
+    pub size: usize,
+}
+
+impl Selection for Tournament {
+    fn select(&self, ranked: &[Fitness], rng: &mut dyn RngCore) -> usize {
+        // Sorted best-first, so the smallest index wins.
+        index::sample(rng, ranked.len(), self.size.min(ranked.len()))
+            .into_iter()
+            .min()
+            .unwrap()
+    }
+}
+
+/// Chance proportional to `1 / (1 + penalty)`.
+pub struct RouletteWheel;
+
+impl Selection for RouletteWheel {
+    fn select(&self, ranked: &[Fitness], rng: &mut dyn RngCore) -> usize {
+        let weight = |fitness: &Fitness| 1.0 / (1.0 + fitness.penalty() as f64);
+        let total: f64 = ranked.iter().map(weight).sum();
+        spin(ranked.iter().map(weight), total, rng)
+    }
+}
+
+/// Chance proportional to position: the best of `n` gets weight `n`, the
+/// worst weight 1.
+pub struct Rank;
+
+impl Selection for Rank {
+    fn select(&self, ranked: &[Fitness], rng: &mut dyn RngCore) -> usize {
+        let n = ranked.len();
+        let total = (n * (n + 1) / 2) as f64;
+        spin((0..n).map(|i| (n - i) as f64), total, rng)
+    }
+}
+
+/// Uniformly from the best `fraction` of the population.
+pub struct Truncation {
+    pub fraction: f64,
+}
+
+impl Selection for Truncation {
+    fn select(&self, ranked: &[Fitness], rng: &mut dyn RngCore) -> usize {
+        let cutoff = ((ranked.len() as f64 * self.fraction).ceil() as usize).clamp(1, ranked.len());
+        rng.random_range(0..cutoff)
+    }
+}
+
+/// Index at which a random point in `0..total` falls along `weights`.
+fn spin(weights: impl Iterator<Item = f64>, total: f64, rng: &mut dyn RngCore) -> usize {
+    let mut point = rng.random_range(0.0..total);
+    let mut last = 0;
+    for (i, weight) in weights.enumerate() {
+        if point < weight {
+            return i;
+        }
+        point -= weight;
+        last = i;
+    }
+    last
+}
+
+/// Which [`Selection`] a solver run uses.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    /// [`Tournament`] of [`GaConfig::tournament_size`](crate::GaConfig::tournament_size).
+    Tournament,
+    RouletteWheel,
+    Rank,
+    Truncation { fraction: f64 },
+}
+
+impl SelectionStrategy {
+    pub fn build(self, tournament_size: usize) -> Box<dyn Selection> {
+        match self {
+            SelectionStrategy::Tournament => Box::new(Tournament { size: tournament_size }),
+            SelectionStrategy::RouletteWheel => Box::new(RouletteWheel),
+            SelectionStrategy::Rank => Box::new(Rank),
+            SelectionStrategy::Truncation { fraction } => Box::new(Truncation { fraction }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// Ten individuals, best first, each one hard violation worse than the last.
+    fn ranked() -> Vec<Fitness> {
+        (0..10)
+            .map(|hard| Fitness {
+                hard,
+                soft: 0,
+                breakdown: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// How often each index is picked in `draws` selections.
+    fn picks(selection: &dyn Selection, draws: usize) -> Vec<usize> {
+        let ranked = ranked();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = vec![0; ranked.len()];
+        for _ in 0..draws {
+            counts[selection.select(&ranked, &mut rng)] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn truncation_stays_within_the_cutoff() {
+        let counts = picks(&Truncation { fraction: 0.3 }, 1_000);
+        assert!(counts[..3].iter().all(|&count| count > 0));
+        assert!(counts[3..].iter().all(|&count| count == 0));
+
+        // However small the fraction, the best individual remains.
+        let counts = picks(&Truncation { fraction: 0.0 }, 100);
+        assert_eq!(counts[0], 100);
+    }
+
+    #[test]
+    fn tournament_of_everyone_picks_the_best() {
+        let counts = picks(&Tournament { size: 10 }, 100);
+        assert_eq!(counts[0], 100);
+        let counts = picks(&Tournament { size: 20 }, 100);
+        assert_eq!(counts[0], 100);
+    }
+
+    #[test]
+    fn proportional_strategies_favour_the_best() {
+        for selection in [&Rank as &dyn Selection, &RouletteWheel] {
+            let counts = picks(selection, 10_000);
+            assert!(counts[0] > counts[1]);
+            assert!(counts[1] > counts[9]);
+        }
+    }
+
+    #[test]
+    fn spin_stays_in_range() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..1_000 {
+            // Ten times 0.1 adds up to just under 1.
+            assert!(spin(std::iter::repeat_n(0.1, 10), 1.0, &mut rng) < 10);
+            // A point past the last weight falls on the last individual.
+            assert!(spin([1.0, 1.0].into_iter(), 3.0, &mut rng) < 2);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::chromosome::Gene;
+use crate::constraint::Fitness;
+
+/// A snapshot of one generation, handed to the progress callback of
+/// [`genetic_algorithm_with_progress`](crate::ga::genetic_algorithm_with_progress).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GenerationStats {
+    pub generation: usize,
+    /// Best, mean and worst combined penalty (see [`Fitness::penalty`]).
+    pub best: u64,
+    pub mean: f64,
+    pub worst: u64,
+    /// Hard and soft penalty of the generation's best schedule.
+    pub best_hard: u64,
+    pub best_soft: u64,
+    /// Mean hard and soft penalty across the population.
+    pub mean_hard: f64,
+    pub mean_soft: f64,
+    /// Number of schedules without hard violations.
+    pub feasible: usize,
+    /// Mean share of classes placed differently from the generation's best
+    /// schedule, from 0 (converged) to 1.
+    pub diversity: f64,
+    /// Time since the run started.
+    pub elapsed: Duration,
+}
+
+impl GenerationStats {
+    /// Summarises a population made of one or more sub-populations, given
+    /// as schedules and their fitness, each sorted best-first.
+    pub fn collect(generation: usize, populations: &[(&[Vec<Gene>], &[Fitness])], elapsed: Duration) -> Self {
+        let fitness = || populations.iter().flat_map(|&(_, fitness)| fitness);
+        let (best, best_fitness) = populations
+            .iter()
+            .map(|&(schedules, fitness)| (&schedules[0], &fitness[0]))
+            .min_by_key(|(_, fitness)| fitness.key())
+            .unwrap();
+        let count = fitness().count() as f64;
+        let differing: usize = populations
+            .iter()
+            .flat_map(|&(schedules, _)| schedules)
+            .map(|schedule| schedule.iter().zip(best).filter(|(class, best)| class != best).count())
+            .sum();
+
+        Self {
+            generation,
+            best: best_fitness.penalty(),
+            mean: fitness().map(|fitness| fitness.penalty() as f64).sum::<f64>() / count,
+            worst: fitness().map(Fitness::penalty).max().unwrap(),
+            best_hard: best_fitness.hard,
+            best_soft: best_fitness.soft,
+            mean_hard: fitness().map(|fitness| fitness.hard as f64).sum::<f64>() / count,
+            mean_soft: fitness().map(|fitness| fitness.soft as f64).sum::<f64>() / count,
+            feasible: fitness().filter(|fitness| fitness.is_feasible()).count(),
+            diversity: differing as f64 / (count * best.len().max(1) as f64),
+            elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fitness(hard: u64, soft: u64) -> Fitness {
+        Fitness { hard, soft, breakdown: Vec::new() }
+    }
+
+    #[test]
+    fn summarises_every_island() {
+        let first = vec![vec![Gene::new(0, 0, 0)], vec![Gene::new(0, 0, 1)]];
+        let first_fitness = [fitness(1, 0), fitness(2, 4)];
+        let second = vec![vec![Gene::new(0, 0, 1)], vec![Gene::new(0, 0, 1)]];
+        let second_fitness = [fitness(0, 3), fitness(1, 1)];
+
+        let stats = GenerationStats::collect(
+            4,
+            &[(&first, &first_fitness), (&second, &second_fitness)],
+            Duration::ZERO,
+        );
+        assert_eq!((stats.best, stats.best_hard, stats.best_soft), (3, 0, 3));
+        assert_eq!(stats.worst, 2004);
+        assert_eq!((stats.mean_hard, stats.mean_soft), (1.0, 2.0));
+        assert_eq!(stats.feasible, 1);
+        // Only the first island's best differs from the overall best.
+        assert_eq!(stats.diversity, 0.25);
+    }
+}
@@ -0,0 +1,76 @@
+//! Fixtures shared by the unit tests.
+
+use chrono::NaiveTime;
+
+use crate::problem::{Course, Instructor, LoadLimits, Problem, Room, Student};
+use crate::schedule::{Days, TimeBlock};
+
+pub fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// A small term with more classes than comfortably fit, so random schedules
+/// break every kind of rule.
+pub fn problem() -> Problem {
+    let names: Vec<String> = (0..12).map(|i| format!("Course {}", i)).collect();
+    let courses = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let course = if i % 2 == 0 {
+                Course::new(name, 60, 180)
+            } else {
+                Course::new(name, 90, 180)
+            };
+            let course = course
+                .with_enrollment(20 + 5 * i as u32)
+                .for_groups(&[&format!("Cohort {}", i % 3)]);
+            if i % 4 == 1 {
+                course.requires(&["lab"])
+            } else {
+                course
+            }
+        })
+        .collect();
+
+    let qualified = |i: usize| -> Vec<&str> {
+        names
+            .iter()
+            .enumerate()
+            .filter(|(c, _)| c % 4 == i || (c + 1) % 4 == i)
+            .map(|(_, name)| name.as_str())
+            .collect()
+    };
+    let weekdays: Days = "MTWThF".parse().unwrap();
+    let instructors = vec![
+        Instructor::new("Ana", &qualified(0)).with_preferences(&[TimeBlock::new(weekdays, time(8, 0), time(11, 0))]),
+        Instructor::new("Ben", &qualified(1))
+            .with_availability(&[TimeBlock::new("MTWTh".parse().unwrap(), time(8, 0), time(17, 0))]),
+        Instructor::new("Cruz", &qualified(2)).with_limits(LoadLimits {
+            max_daily_minutes: Some(120),
+            max_weekly_minutes: Some(360),
+            max_consecutive: Some(1),
+        }),
+        Instructor::new("Dee", &qualified(3)),
+    ];
+
+    let rooms = vec![
+        Room::new("Hall", 80),
+        Room::new("Room A", 40),
+        Room::new("Room B", 30),
+        Room::new("Lab", 70).with_features(&["lab"]),
+    ];
+
+    let students = (0..20)
+        .map(|i| {
+            let enrolled: Vec<&str> = [i % 12, (i * 5 + 1) % 12, (i * 7 + 3) % 12]
+                .iter()
+                .map(|&c| names[c].as_str())
+                .collect();
+            Student::new(&format!("Student {}", i), &enrolled)
+        })
+        .collect();
+
+    let times = [time(8, 0), time(9, 0), time(9, 30), time(11, 0)];
+    Problem::new(courses, instructors, rooms, &[Days::MWF, Days::TTH], &times).with_students(students)
+}
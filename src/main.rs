@@ -1,140 +1,117 @@
-use rand::prelude::*;
-use rand::rng;
-use chrono::{NaiveTime, Duration};
-
-#[derive(Debug, Clone)]
-struct ClassSchedule {
-    course: String,
-    instructor: String,
-    room: String,
-    start_time: NaiveTime,
-    end_time: NaiveTime,
-}
-
-impl ClassSchedule {
-    fn new(course: &str, instructor: &str, room: &str, start_time: NaiveTime, duration: i64) -> Self {
-        Self {
-            course: course.to_string(),
-            instructor: instructor.to_string(),
-            room: room.to_string(),
-            start_time,
-            end_time: start_time + Duration::minutes(duration),
-        }
-    }
-}
-
-fn generate_random_schedule(courses: &[&str], instructors: &[&str], rooms: &[&str], times: &[NaiveTime]) -> Vec<ClassSchedule> {
-    let mut rng = rng();
-    let mut schedule = Vec::new();
-
-    for &course in courses {
-        let instructor = instructors.choose(&mut rng).unwrap();
-        let room = rooms.choose(&mut rng).unwrap();
-        let start_time = times.choose(&mut rng).unwrap();
-        let duration = [60, 90].choose(&mut rng).unwrap();
-
-        schedule.push(ClassSchedule::new(course, instructor, room, *start_time, *duration));
-    }
-
-    schedule
-}
-
-fn calculate_conflicts(schedule: &[ClassSchedule]) -> i32 {
-    let mut conflicts = 0;
-
-    for (i, class1) in schedule.iter().enumerate() {
-        for class2 in &schedule[i + 1..] {
-            if class1.instructor == class2.instructor && classes_overlap(class1, class2) {
-                println!("Conflict: Instructor {} has overlapping classes {} and {}", class1.instructor, class1.course, class2.course);
-                conflicts += 1;
-            }
-            if class1.room == class2.room && classes_overlap(class1, class2) {
-                println!("Conflict: Room {} is double-booked for {} and {}", class1.room, class1.course, class2.course);
-                conflicts += 1;
-            }
+use std::{env, fs, process};
+
+use chrono::NaiveTime;
+use logic::{
+    genetic_algorithm_with_progress, Course, Days, FitnessModel, GaConfig, Instructor, LoadLimits, Problem, Room, Student,
+    TimeBlock, TimeGrid,
+};
+
+fn load_config() -> GaConfig {
+    match env::args().nth(1) {
+        Some(path) => {
+            let contents = fs::read_to_string(&path).unwrap_or_else(|err| {
+                eprintln!("Cannot read config {}: {}", path, err);
+                process::exit(1);
+            });
+            serde_json::from_str(&contents).unwrap_or_else(|err| {
+                eprintln!("Invalid config {}: {}", path, err);
+                process::exit(1);
+            })
         }
+        None => GaConfig { generations: 50, ..GaConfig::default() },
     }
-
-    conflicts
-}
-
-fn classes_overlap(class1: &ClassSchedule, class2: &ClassSchedule) -> bool {
-    class1.start_time < class2.end_time && class2.start_time < class1.end_time
 }
 
-fn mutate(schedule: &mut Vec<ClassSchedule>, rooms: &[&str], times: &[NaiveTime]) {
-    let mut rng = rng();
-    let index = rng.random_range(0..schedule.len());
-    
-    schedule[index].room = rooms.choose(&mut rng).unwrap().to_string();
-    schedule[index].start_time = *times.choose(&mut rng).unwrap();
-    schedule[index].end_time = schedule[index].start_time + Duration::minutes(60);
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
 }
 
-fn crossover(parent1: &[ClassSchedule], parent2: &[ClassSchedule]) -> Vec<ClassSchedule> {
-    let mut rng = rng();
-    let crossover_point = rng.random_range(0..parent1.len());
-    
-    let mut child = Vec::new();
-    child.extend_from_slice(&parent1[..crossover_point]);
-    child.extend_from_slice(&parent2[crossover_point..]);
-
-    child
-}
-
-fn genetic_algorithm(
-    courses: &[&str],
-    instructors: &[&str],
-    rooms: &[&str],
-    times: &[NaiveTime],
-    generations: usize,
-) -> Vec<ClassSchedule> {
-    let mut population: Vec<Vec<ClassSchedule>> = (0..10)
-        .map(|_| generate_random_schedule(courses, instructors, rooms, times))
-        .collect();
-
-    for _ in 0..generations {
-        population.sort_by_key(|schedule| calculate_conflicts(schedule));
-        
-        let parents = &population[..2]; // Best 2 schedules
-        let mut new_population = Vec::new();
-
-        for _ in 0..5 {
-            let child = crossover(&parents[0], &parents[1]);
-            new_population.push(child);
-        }
-
-        for mut schedule in &mut new_population {
-            mutate(&mut schedule, rooms, times);
-        }
-
-        population = new_population;
-    }
-
-    population[0].clone()
-}
-
-
 fn main() {
-    let courses = ["Math", "Science", "History", "English"];
-    let instructors = ["Alice", "Bob", "Charlie"];
-    let rooms = ["Room 101", "Room 102", "Room 103"];
-    let times: Vec<NaiveTime> = [
-        NaiveTime::from_hms_opt(8, 0, 0),
-        NaiveTime::from_hms_opt(9, 30, 0),
-        NaiveTime::from_hms_opt(11, 0, 0),
-    ]
-    .into_iter()
-    .flatten()
-    .collect();
-
-    let best_schedule = genetic_algorithm(&courses, &instructors, &rooms, &times, 50);
+    let weekdays: Days = "MTWThF".parse().unwrap();
+    let courses = vec![
+        Course::new("Math", 60, 180).with_enrollment(40).for_groups(&["BSCS 1"]),
+        Course::new("Science", 90, 180).with_enrollment(25).requires(&["lab"]),
+        Course::new("History", 60, 180).with_enrollment(30),
+        Course::new("English", 90, 180).with_enrollment(20).for_groups(&["BSCS 1"]),
+    ];
+    let instructors = vec![
+        Instructor::new("Alice", &["Math", "Science"])
+            .with_preferences(&[TimeBlock::new(weekdays, time(8, 0), time(12, 0))]),
+        Instructor::new("Bob", &["Science", "History", "English"])
+            .with_availability(&[TimeBlock::new("MTWTh".parse().unwrap(), time(8, 0), time(17, 0))]),
+        Instructor::new("Charlie", &["Math", "History", "English"]).with_limits(LoadLimits {
+            max_daily_minutes: Some(180),
+            max_weekly_minutes: Some(540),
+            max_consecutive: Some(2),
+        }),
+    ];
+    let rooms = vec![
+        Room::new("Room 101", 45),
+        Room::new("Room 102", 30),
+        Room::new("Room 103", 25).with_features(&["lab"]),
+    ];
+    let patterns = [Days::MWF, Days::TTH];
+    // 90-minute periods from 08:00 with a half-hour break after the second
+    // and lunch from 13:00 to 14:00.
+    let grid = TimeGrid::new(weekdays, time(8, 0), 90, 5)
+        .with_break(1, 30)
+        .with_blocks(&[TimeBlock::new(weekdays, time(13, 0), time(14, 0))]);
+
+    let students = vec![
+        Student::new("2024-0001", &["Math", "Science", "English"]),
+        Student::new("2024-0002", &["Science", "History"]),
+        Student::new("2024-0003", &["History", "English"]),
+    ];
+
+    let problem = Problem::on_grid(courses, instructors, rooms, &patterns, grid).with_students(students);
+    let config = load_config();
+    let model = FitnessModel::for_problem(&problem);
+    let solution = genetic_algorithm_with_progress(&problem, &model, &config, |stats| {
+        if stats.generation % 10 == 0 {
+            eprintln!(
+                "Generation {}: best {} (hard {}, soft {}), mean {:.1} (hard {:.1}, soft {:.1}), worst {}, {} feasible, diversity {:.2}, {:.2?}",
+                stats.generation,
+                stats.best,
+                stats.best_hard,
+                stats.best_soft,
+                stats.mean,
+                stats.mean_hard,
+                stats.mean_soft,
+                stats.worst,
+                stats.feasible,
+                stats.diversity,
+                stats.elapsed
+            );
+        }
+    })
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
 
     println!("Optimized Schedule:");
-    for class in &best_schedule {
-        println!("{:?}", class);
+    for class in &solution.schedule {
+        println!(
+            "{} | {} | {} | {} {}-{}",
+            class.course,
+            class.instructor,
+            class.room,
+            class.days,
+            class.start_time.format("%H:%M"),
+            class.end_time.format("%H:%M")
+        );
     }
 
-    let conflicts = calculate_conflicts(&best_schedule);
-    println!("Total Conflicts: {}", conflicts);
+    for score in &solution.fitness.breakdown {
+        println!("{:?} {}: {} x {}", score.hardness, score.name, score.violations, score.weight);
+    }
+    for conflict in &solution.conflicts {
+        println!("Conflict: {}", conflict);
+    }
+    println!("Hard penalty: {}, Soft penalty: {}", solution.fitness.hard, solution.fitness.soft);
+    println!(
+        "Found in generation {} of {} ({:?})",
+        solution.generation, solution.generations, solution.stop_reason
+    );
+    println!("Seed: {}", solution.seed);
 }